@@ -1,7 +1,9 @@
 use std::{
     env,
-    time::Duration,
-    collections::HashMap,
+    fmt,
+    cmp::Ordering,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+    collections::{HashMap, HashSet},
     sync::{RwLock, Arc},
 };
 use serenity::{
@@ -14,7 +16,7 @@ use serenity::{
         application::interaction::InteractionResponseType,
         application::component::InputTextStyle,
         application::component::ButtonStyle,
-        prelude::{component::ActionRowComponent, ChannelId, UserId, User, MessageId},
+        prelude::{component::ActionRowComponent, ChannelId, UserId, User, MessageId, RoleId, Member},
     },
     collector::{ModalInteractionCollectorBuilder, ComponentInteractionCollectorBuilder},
     builder::CreateComponents,
@@ -22,7 +24,9 @@ use serenity::{
 };
 use tallystick::{
     approval::DefaultApprovalTally,
-    borda::DefaultBordaTally,
+    condorcet::DefaultCondorcetTally,
+    plurality::DefaultPluralityTally,
+    schulze::DefaultSchulzeTally,
     score::ScoreTally,
 };
 
@@ -40,6 +44,17 @@ const ID_BUILD_PROMPT_INPUT: &str = "BuildPromptModal";
 const ID_BUILD_PROMPT_INPUT_TXT: &str = "BuildPromptIn";
 const ID_BUILD_PING_BTN: &str = "PingBtn";
 const ID_BUILD_DUR_BTN: &str = "DurBtn";
+const ID_BUILD_WINNERS_BTN: &str = "WinnersBtn";
+const ID_BUILD_BORDA_BTN: &str = "BordaBtn";
+const ID_BUILD_SCORE_BTN: &str = "ScoreBtn";
+const ID_BUILD_TIE_BTN: &str = "TieBtn";
+const ID_BUILD_DECIDE_BTN: &str = "DecideBtn";
+const ID_BUILD_NONE_BTN: &str = "NoneBtn";
+const ID_BUILD_WRITEIN_BTN: &str = "WriteInBtn";
+const ID_BUILD_SELECT_BTN: &str = "SelectBtn";
+const ID_BUILD_VETO_BTN: &str = "VetoBtn";
+const ID_BUILD_ROLES_BTN: &str = "RolesBtn";
+const ID_BUILD_BUDGET_BTN: &str = "BudgetBtn";
 const ID_BUILD_CHOICE_BTN: &str = "ValBtn";
 const ID_BUILD_SUBMIT: &str = "BuildSubmit";
 const ID_BUILD_CANCEL: &str = "BuildCancel";
@@ -47,17 +62,48 @@ const ID_BUILD_VAL_INPUT: &str = "BuildValModal";
 const ID_BUILD_VAL_INPUT_TXT: &str = "BuildValIn";
 const ID_BUILD_DUR_INPUT: &str = "BuildDurModal";
 const ID_BUILD_DUR_INPUT_TXT: &str = "BuildDurIn";
+const ID_BUILD_WINNERS_INPUT: &str = "BuildWinnersModal";
+const ID_BUILD_WINNERS_INPUT_TXT: &str = "BuildWinnersIn";
+const ID_BUILD_SCORE_INPUT: &str = "BuildScoreModal";
+const ID_BUILD_SCORE_INPUT_MIN: &str = "BuildScoreMinIn";
+const ID_BUILD_SCORE_INPUT_MAX: &str = "BuildScoreMaxIn";
+const ID_BUILD_SCORE_INPUT_STEP: &str = "BuildScoreStepIn";
+const ID_BUILD_SCORE_INPUT_BUDGET: &str = "BuildScoreBudgetIn";
+const ID_BUILD_SELECT_INPUT: &str = "BuildSelectModal";
+const ID_BUILD_SELECT_INPUT_MIN: &str = "BuildSelectMinIn";
+const ID_BUILD_SELECT_INPUT_MAX: &str = "BuildSelectMaxIn";
+const ID_BUILD_ROLES_INPUT: &str = "BuildRolesModal";
+const ID_BUILD_ROLES_INPUT_TXT: &str = "BuildRolesIn";
+const ID_BUILD_BUDGET_INPUT: &str = "BuildBudgetModal";
+const ID_BUILD_BUDGET_INPUT_TXT: &str = "BuildBudgetIn";
+const ID_BUILD_DECIDE_INPUT: &str = "BuildDecideModal";
+const ID_BUILD_DECIDE_INPUT_QUORUM: &str = "BuildDecideQuorumIn";
+const ID_BUILD_DECIDE_INPUT_THRESHOLD: &str = "BuildDecideThresholdIn";
 const ID_SUG_VAL_BTN: &str = "SugVBtn";
 const ID_SUG_VAL_INPUT: &str = "SugVBtn";
 const ID_SUG_VAL_INPUT_TXT: &str = "SugVBtn";
 const ID_SUG_SUB_BTN: &str = "SugSubBtn";
+const ID_VETO_BTN: &str = "VetoMainBtn";
+const ID_VETO_SUB_BTN: &str = "VetoSubBtn";
+const ID_VETO_VAL_PREFIX: &str = "VetoVal";
+const ID_VETO_LEFT: &str = "VetoLeft";
+const ID_VETO_RIGHT: &str = "VetoRight";
 const ID_VOTE_VAL_PREFIX: &str = "VoteVal";
+const ID_VOTE_PLUS_PREFIX: &str = "VotePlus";
+const ID_VOTE_MINUS_PREFIX: &str = "VoteMinus";
 const ID_VOTE_VAL_INPUT: &str = "ValInModal";
 const ID_VOTE_VAL_INPUT_PREFIX: &str = "ValIn";
 const ID_VOTE_BTN: &str = "MainVoteBtn";
 const ID_VOTE_LEFT: &str = "VoteLeft";
 const ID_VOTE_RIGHT: &str = "VoteRight";
 const ID_VOTE_SUBMIT: &str = "VoteSubmit";
+const ID_VOTE_TIE_PREFIX: &str = "VoteTie";
+const ID_VOTE_RUNOFF: &str = "VoteRunoff";
+const ID_VOTE_ABSTAIN: &str = "VoteAbstain";
+const ID_VOTE_REOPEN: &str = "VoteReopen";
+const ID_VOTE_WRITEIN: &str = "VoteWriteIn";
+const ID_VOTE_WRITEIN_INPUT: &str = "WriteInModal";
+const ID_VOTE_WRITEIN_INPUT_TXT: &str = "WriteInIn";
 
 const VOTE_DM_CONT: &str = "Create a new Vote:";
 
@@ -65,7 +111,17 @@ const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60*90);
 const VOTE_DM_TIMEOUT: Duration = Duration::from_secs(60*60*1);
 const MAX_DUR_HR: f64 = 24.0*6.0;
 const MIN_DUR_HR: f64 = 0.01;
+const MAX_WINNERS: usize = 10;
+const MAX_ROLE_WEIGHT: u64 = 10;
+const MAX_MSG_LEN: usize = 2000; // discord won't take a longer message
+const RESULTS_MARGIN: usize = 400; // room for what gets put around the results, like a no decision reason
+const MAX_PAIR_LINES: usize = 12;
+const MAX_TIE_BTNS: usize = 20; // four rows of five, leaving a row for the runoff
+const MAX_BUDGET_HR: f64 = 24.0;
+const MAX_CHOICE_LEN: usize = 33;
 const PERPAGE: usize = 4;
+const VETO_PERPAGE: usize = 20; // four rows of five, leaving a row for the page buttons
+const NONE_CHOICE: &str = "None of these";
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 struct VoteType(u32);
@@ -73,6 +129,26 @@ const VOTE_APPROVAL: VoteType   = VoteType(1 << 0);
 const VOTE_SCORE: VoteType      = VoteType(1 << 1);
 const VOTE_LSCORE: VoteType     = VoteType(1 << 2);
 const VOTE_BORDA: VoteType      = VoteType(1 << 3);
+const VOTE_PLURALITY: VoteType  = VoteType(1 << 4);
+const VOTE_IRV: VoteType        = VoteType(1 << 5);
+const VOTE_SCHULZE: VoteType    = VoteType(1 << 6);
+const VOTE_RPAIRS: VoteType     = VoteType(1 << 7);
+const VOTE_STV: VoteType        = VoteType(1 << 8);
+const VOTE_STAR: VoteType       = VoteType(1 << 9);
+const VOTE_MJ: VoteType         = VoteType(1 << 10);
+const VOTE_CUMULATIVE: VoteType = VoteType(1 << 11);
+const VOTE_QUADRATIC: VoteType  = VoteType(1 << 12);
+const VOTE_COPELAND: VoteType   = VoteType(1 << 13);
+const VOTE_MINIMAX: VoteType    = VoteType(1 << 14);
+const VOTE_RBALLOT: VoteType    = VoteType(1 << 15);
+const VOTE_ALOTTERY: VoteType   = VoteType(1 << 16);
+const VOTE_SLOTTERY: VoteType   = VoteType(1 << 17);
+const VOTE_PAV: VoteType        = VoteType(1 << 18);
+const VOTE_SPAV: VoteType       = VoteType(1 << 19);
+const VOTE_TAPPROVAL: VoteType  = VoteType(1 << 20);
+const VOTE_TSCORE: VoteType     = VoteType(1 << 21);
+
+const MJ_GRADES: [&str; 5] = ["Reject", "Poor", "Fair", "Good", "Excellent"];
 
 impl ToString for VoteType {
     fn to_string(&self) -> String {
@@ -81,6 +157,24 @@ impl ToString for VoteType {
             VOTE_SCORE => "Score".into(),
             VOTE_LSCORE => "Limited Score".into(),
             VOTE_BORDA => "Borda".into(),
+            VOTE_PLURALITY => "Plurality".into(),
+            VOTE_IRV => "Instant Runoff".into(),
+            VOTE_SCHULZE => "Schulze".into(),
+            VOTE_RPAIRS => "Ranked Pairs".into(),
+            VOTE_STV => "Single Transferable Vote".into(),
+            VOTE_STAR => "STAR".into(),
+            VOTE_MJ => "Majority Judgment".into(),
+            VOTE_CUMULATIVE => "Cumulative".into(),
+            VOTE_QUADRATIC => "Quadratic".into(),
+            VOTE_COPELAND => "Copeland".into(),
+            VOTE_MINIMAX => "Minimax".into(),
+            VOTE_RBALLOT => "Random Ballot".into(),
+            VOTE_ALOTTERY => "Approval Lottery".into(),
+            VOTE_SLOTTERY => "Score Lottery".into(),
+            VOTE_PAV => "Proportional Approval".into(),
+            VOTE_SPAV => "Sequential Proportional Approval".into(),
+            VOTE_TAPPROVAL => "Approval Time Budget".into(),
+            VOTE_TSCORE => "Score Time Budget".into(),
             _ => panic!("Unknown vote type! {:?}", self),
         }
     }
@@ -93,38 +187,243 @@ impl VoteType {
             "Score" => VOTE_SCORE,
             "Limited Score" => VOTE_LSCORE,
             "Borda" => VOTE_BORDA,
+            "Plurality" => VOTE_PLURALITY,
+            "Instant Runoff" => VOTE_IRV,
+            "Schulze" => VOTE_SCHULZE,
+            "Ranked Pairs" => VOTE_RPAIRS,
+            "Single Transferable Vote" => VOTE_STV,
+            "STAR" => VOTE_STAR,
+            "Majority Judgment" => VOTE_MJ,
+            "Cumulative" => VOTE_CUMULATIVE,
+            "Quadratic" => VOTE_QUADRATIC,
+            "Copeland" => VOTE_COPELAND,
+            "Minimax" => VOTE_MINIMAX,
+            "Random Ballot" => VOTE_RBALLOT,
+            "Approval Lottery" => VOTE_ALOTTERY,
+            "Score Lottery" => VOTE_SLOTTERY,
+            "Proportional Approval" => VOTE_PAV,
+            "Sequential Proportional Approval" => VOTE_SPAV,
+            "Approval Time Budget" => VOTE_TAPPROVAL,
+            "Score Time Budget" => VOTE_TSCORE,
             _ => panic!("Tried to get a vote type from an unknown string! {}", s),
         }
     }
 
-    fn value_name(&self) -> String {
+    fn value_name(&self, ss: &ScoreSettings) -> String {
         match *self {
-            VOTE_APPROVAL => "choice".into(),
-            VOTE_SCORE => "score (-10.0 to 10.0)".into(),
-            VOTE_LSCORE => "score where sum(abs(scores)) <= 10.0".into(),
-            VOTE_BORDA => "rank (1 is 1st choice, 2 second, ...)".into(),
+            vt if vt.is_approval_ballot() => "choice".into(),
+            vt if vt.is_score_ballot() => format!("score ({})", ss.range_str()),
+            VOTE_LSCORE => format!("score where sum(abs(scores)) <= {:.1}", ss.budget),
+            vt if vt.is_ranked() => "rank (1 is 1st choice, 2 second, ..., ties allowed)".into(),
+            VOTE_PLURALITY | VOTE_RBALLOT => "single choice".into(),
+            VOTE_MJ => format!("grade ({})", MJ_GRADES.join(", ")),
+            VOTE_CUMULATIVE => format!("whole number of points, {} in total", ss.budget),
+            VOTE_QUADRATIC => format!("whole votes ({}), costing votes²", ss.range_str()),
             _ => panic!("Tried to get value name for unknown vote type! {:?}", self),
         }
     }
 
-    fn is_bad_value(&self, v: f32, vals: &Vec<String>) -> bool {
+    fn is_bad_value(&self, v: f32, vals: &Vec<String>, ss: &ScoreSettings) -> bool {
         match *self {
-            VOTE_APPROVAL => false,
-            VOTE_SCORE | VOTE_LSCORE => v < -10.0 || v > 10.0,
-            VOTE_BORDA => v.fract() != 0.0 || v <= 0.0 || v > (vals.len() as f32),
+            VOTE_PLURALITY | VOTE_MJ | VOTE_RBALLOT => false,
+            vt if vt.is_approval_ballot() => false,
+            VOTE_LSCORE => ss.is_bad_score(v),
+            vt if vt.is_score_ballot() => ss.is_bad_score(v),
+            vt if vt.is_ranked() => v.fract() != 0.0 || v <= 0.0 || v > (vals.len() as f32),
+            VOTE_CUMULATIVE => v.fract() != 0.0 || v < 0.0 || v > ss.budget,
+            VOTE_QUADRATIC => v.fract() != 0.0 || ss.is_bad_score(v) || v * v > ss.budget,
             _ => panic!("Tried to test value for unknown vote type! {:?}", self),
         }
     }
 
+    // approval style ballots, any number of choices selected
+    fn is_approval_ballot(&self) -> bool {
+        matches!(*self, VOTE_APPROVAL | VOTE_ALOTTERY | VOTE_PAV | VOTE_SPAV | VOTE_TAPPROVAL)
+    }
+
+    // plain score ballots, each choice scored in the score range
+    fn is_score_ballot(&self) -> bool {
+        matches!(*self, VOTE_SCORE | VOTE_STAR | VOTE_SLOTTERY | VOTE_TSCORE)
+    }
+
+    // ranked ballots, ties and unranked choices allowed
+    fn is_ranked(&self) -> bool {
+        matches!(*self, VOTE_BORDA | VOTE_IRV | VOTE_SCHULZE | VOTE_RPAIRS | VOTE_STV | VOTE_COPELAND | VOTE_MINIMAX)
+    }
+
+    fn is_time_budget(&self) -> bool {
+        matches!(*self, VOTE_TAPPROVAL | VOTE_TSCORE)
+    }
+
+    // a runoff between the top choices only makes sense if there are top choices
+    fn has_runoff(&self) -> bool {
+        !matches!(*self, VOTE_RBALLOT | VOTE_ALOTTERY | VOTE_SLOTTERY) && !self.is_time_budget()
+    }
+
     fn get_all() -> Vec<Self> {
-        vec![VOTE_APPROVAL, VOTE_SCORE, VOTE_LSCORE, VOTE_BORDA]
+        vec![VOTE_PLURALITY, VOTE_APPROVAL, VOTE_SCORE, VOTE_LSCORE, VOTE_CUMULATIVE, VOTE_QUADRATIC, VOTE_STAR, VOTE_BORDA, VOTE_IRV, VOTE_SCHULZE, VOTE_RPAIRS, VOTE_COPELAND, VOTE_MINIMAX, VOTE_STV, VOTE_MJ, VOTE_RBALLOT, VOTE_ALOTTERY, VOTE_SLOTTERY, VOTE_PAV, VOTE_SPAV, VOTE_TAPPROVAL, VOTE_TSCORE]
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ScoreSettings {
+    min: f32,
+    max: f32,
+    step: f32, // 0.0 allows any score in the range
+    budget: f32, // max sum(abs(scores)) for limited score
+}
+
+impl ScoreSettings {
+    fn new() -> Self {
+        ScoreSettings {
+            min: -10.0,
+            max: 10.0,
+            step: 0.0,
+            budget: 10.0,
+        }
+    }
+
+    fn range_str(&self) -> String {
+        if self.step == 0.0 {
+            format!("{:.1} to {:.1}", self.min, self.max)
+        } else {
+            format!("{:.1} to {:.1}, steps of {}", self.min, self.max, self.step)
+        }
+    }
+
+    // unscored choices get 0, or the closest score to it that lands on a step
+    fn default_score(&self) -> f32 {
+        let v = 0.0f32.clamp(self.min, self.max);
+        if self.step == 0.0 {
+            return v;
+        }
+        let snapped = self.min + ((v - self.min) / self.step).round() * self.step;
+        if snapped > self.max {
+            snapped - self.step
+        } else {
+            snapped
+        }
+    }
+
+    fn is_bad_score(&self, v: f32) -> bool {
+        // NaN gets past every comparison
+        if !v.is_finite() || v < self.min || v > self.max {
+            return true;
+        }
+        if self.step != 0.0 {
+            // allow for a little float error when checking it lands on a step
+            let steps = (v - self.min) / self.step;
+            return (steps - steps.round()).abs() > 0.001;
+        }
+        false
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SelectLimits {
+    min: usize,
+    max: usize, // 0 for no limit
+}
+
+impl SelectLimits {
+    fn new() -> Self {
+        SelectLimits {
+            min: 0,
+            max: 0,
+        }
+    }
+
+    fn range_str(&self) -> String {
+        match (self.min, self.max) {
+            (0, 0) => "Any".into(),
+            (min, 0) => format!("At Least {}", min),
+            (0, max) => format!("At Most {}", max),
+            (min, max) if min == max => format!("Exactly {}", min),
+            (min, max) => format!("{} to {}", min, max),
+        }
+    }
+
+    fn is_limited(&self) -> bool {
+        self.min > 0 || self.max > 0
     }
 }
 
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum BordaVariant {
+    Borda,
+    Classic,
+    ModifiedClassic,
+    Dowdall,
+}
+
+impl fmt::Display for BordaVariant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            BordaVariant::Borda => "Standard",
+            BordaVariant::Classic => "Classic",
+            BordaVariant::ModifiedClassic => "Modified Classic",
+            BordaVariant::Dowdall => "Dowdall",
+        })
+    }
+}
+
+impl BordaVariant {
+    fn next(&self) -> Self {
+        match *self {
+            BordaVariant::Borda => BordaVariant::Classic,
+            BordaVariant::Classic => BordaVariant::ModifiedClassic,
+            BordaVariant::ModifiedClassic => BordaVariant::Dowdall,
+            BordaVariant::Dowdall => BordaVariant::Borda,
+        }
+    }
 
-#[derive(Debug)]
+    fn get_variant(&self) -> tallystick::borda::Variant<f64> {
+        match *self {
+            BordaVariant::Borda => tallystick::borda::Variant::Borda,
+            BordaVariant::Classic => tallystick::borda::Variant::ClassicBorda,
+            BordaVariant::ModifiedClassic => tallystick::borda::Variant::ModifiedClassicBorda,
+            BordaVariant::Dowdall => tallystick::borda::Variant::Dowdall,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum TieBreak {
+    ShowAll,
+    Random,
+    Creator,
+    Borda,
+    Runoff,
+}
+
+impl fmt::Display for TieBreak {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            TieBreak::ShowAll => "List All",
+            TieBreak::Random => "Random Draw",
+            TieBreak::Creator => "Creator Picks",
+            TieBreak::Borda => "Borda Count",
+            TieBreak::Runoff => "Runoff Vote",
+        })
+    }
+}
+
+impl TieBreak {
+    fn next(&self) -> Self {
+        match *self {
+            TieBreak::ShowAll => TieBreak::Random,
+            TieBreak::Random => TieBreak::Creator,
+            TieBreak::Creator => TieBreak::Borda,
+            TieBreak::Borda => TieBreak::Runoff,
+            TieBreak::Runoff => TieBreak::ShowAll,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 struct VoteInfo {
     kind: VoteType,
+    creator: UserId,
     prompt: String,
     take_sugs: bool,
     show_at_timeout: bool,
@@ -133,13 +432,27 @@ struct VoteInfo {
     allow_early_stop: bool, // TODO
     ping_chan: u8,
     timeout: Duration,
+    num_winners: usize,
+    borda_variant: BordaVariant,
+    score_settings: ScoreSettings,
+    select_limits: SelectLimits,
+    tie_break: TieBreak,
+    quorum: usize, // minimum voters, 0 for none
+    threshold: f32, // percent of voters a winner needs as a top choice, 0 for none
+    none_option: bool,
+    write_ins: bool,
+    veto_phase: bool,
+    vetoes: Vec<(String, usize)>, // choices struck in the veto phase, and by how many
+    role_weights: HashMap<RoleId, u64>, // members with these roles count more, the highest one is used
+    time_budget: u32, // minutes to fill with the picked choices, 0 for none
     vals: Vec<String>,
 }
 
 impl VoteInfo {
-    fn new() -> Self {
+    fn new(creator: UserId) -> Self {
         VoteInfo {
             kind: VOTE_APPROVAL,
+            creator,
             prompt: "".into(),
             take_sugs: false,
             show_at_timeout: true,
@@ -148,12 +461,27 @@ impl VoteInfo {
             allow_early_stop: true,
             ping_chan: 0,
             timeout: DEFAULT_TIMEOUT,
+            num_winners: 1,
+            borda_variant: BordaVariant::Borda,
+            score_settings: ScoreSettings::new(),
+            select_limits: SelectLimits::new(),
+            tie_break: TieBreak::ShowAll,
+            quorum: 0,
+            threshold: 0.0,
+            none_option: false,
+            write_ins: false,
+            veto_phase: false,
+            vetoes: Vec::new(),
+            role_weights: HashMap::new(),
+            time_budget: 0,
             vals: Vec::new(),
         }
     }
 
     fn submittable(&self) -> bool {
-        self.vals.len() > 1 || self.take_sugs
+        // time budget votes have nothing to fill without a budget
+        let has_budget = self.time_budget > 0 || !self.kind.is_time_budget();
+        (self.vals.len() > 1 || self.take_sugs) && has_budget
     }
 
     fn get_timeout_str(&self, suffix: &str) -> String {
@@ -167,6 +495,24 @@ impl VoteInfo {
         }
     }
 
+    fn get_kind_str(&self) -> String {
+        if self.kind == VOTE_BORDA {
+            format!("{} ({})", self.kind.to_string(), self.borda_variant)
+        } else if self.kind.is_time_budget() {
+            format!("{} ({})", self.kind.to_string(), minutes_str(self.time_budget))
+        } else {
+            self.kind.to_string()
+        }
+    }
+
+    fn get_decide_str(&self) -> String {
+        if self.quorum == 0 && self.threshold == 0.0 {
+            "No Quorum or Threshold".into()
+        } else {
+            format!("Min Voters = {}, Winner Needs = {}%", self.quorum, self.threshold)
+        }
+    }
+
     fn get_ping(&self) -> String {
         match self.ping_chan {
             0 => "".into(),
@@ -180,16 +526,22 @@ impl VoteInfo {
 enum CastVotes {
     Select(Vec<usize>), // one or more choices, used for normal or approval voting
     Score(HashMap<usize, f32>), // choices associated with a value
-    Rank(HashMap<usize, usize>), // rank voting
+    Rank(HashMap<usize, usize>), // rank voting, choices can share a rank and unranked choices are left out
+    Grade(HashMap<usize, usize>), // choices associated with an index into MJ_GRADES
+    Points(HashMap<usize, usize>), // whole points spread over the choices, out of a budget
 }
 
 impl CastVotes {
     fn new(vt: VoteType) -> Self {
         match vt {
-            VOTE_APPROVAL => CastVotes::Select(Vec::new()),
-            VOTE_SCORE => CastVotes::Score(HashMap::new()),
+            vt if vt.is_approval_ballot() => CastVotes::Select(Vec::new()),
+            vt if vt.is_score_ballot() => CastVotes::Score(HashMap::new()),
             VOTE_LSCORE => CastVotes::Score(HashMap::new()),
-            VOTE_BORDA => CastVotes::Rank(HashMap::new()),
+            VOTE_QUADRATIC => CastVotes::Score(HashMap::new()),
+            vt if vt.is_ranked() => CastVotes::Rank(HashMap::new()),
+            VOTE_MJ => CastVotes::Grade(HashMap::new()),
+            VOTE_CUMULATIVE => CastVotes::Points(HashMap::new()),
+            VOTE_PLURALITY | VOTE_RBALLOT => CastVotes::Select(Vec::new()),
             _ => panic!("Tried to create CastVotes with unknown vote type"),
         }
     }
@@ -204,13 +556,14 @@ impl CastVotes {
             CastVotes::Score(_m) => {
                 panic!("Tried to get a ordered vec, but have a Score type");
             },
-            CastVotes::Rank(m) => {
-                let mut vt: Vec<(usize, usize)> = Vec::new();
-                for (u, f) in m {
-                    vt.push((*u,*f));
-                }
-                vt.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-                vt.iter().map(|x| x.0).collect()
+            CastVotes::Rank(_) => {
+                self.get_rank_groups().concat()
+            },
+            CastVotes::Grade(_) => {
+                panic!("Tried to get a ordered vec, but have a Grade type");
+            },
+            CastVotes::Points(_) => {
+                panic!("Tried to get a ordered vec, but have a Points type");
             },
         }
     }
@@ -231,13 +584,133 @@ impl CastVotes {
             CastVotes::Rank(_) => {
                 panic!("Tried to get weighted vec but have a rank");
             },
+            CastVotes::Grade(_) => {
+                panic!("Tried to get weighted vec but have a grade");
+            },
+            CastVotes::Points(m) => {
+                m.iter().map(|(u, p)| (*u, *p as f32)).collect()
+            },
         }
     }
 
-    fn are_valid(&self, vt: VoteType, size: usize) -> bool {
-        match vt {
-            VOTE_APPROVAL => true,
-            VOTE_SCORE => true,
+    // the ranked choices from first to last, with choices that share a rank grouped together
+    fn get_rank_groups(&self) -> Vec<Vec<usize>> {
+        match self {
+            CastVotes::Rank(m) => {
+                let mut vt: Vec<(usize, usize)> = m.iter().map(|(c, r)| (*c, *r)).collect();
+                vt.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+
+                let mut groups: Vec<Vec<usize>> = Vec::new();
+                let mut last_rank = 0;
+                for (c, r) in vt {
+                    if groups.is_empty() || r != last_rank {
+                        groups.push(Vec::new());
+                        last_rank = r;
+                    }
+                    groups.last_mut().unwrap().push(c);
+                }
+                groups
+            },
+            _ => panic!("Tried to get rank groups but don't have a rank"),
+        }
+    }
+
+    // every choice with its place, ties share a place and unranked choices tie for last
+    fn get_ranked_vec(&self, size: usize) -> Vec<(usize, u32)> {
+        let groups = self.get_rank_groups();
+        let mut ranked: Vec<(usize, u32)> = Vec::new();
+        for (i, g) in groups.iter().enumerate() {
+            ranked.extend(g.iter().map(|c| (*c, i as u32)));
+        }
+        for c in 0..size {
+            if !ranked.iter().any(|(rc, _)| *rc == c) {
+                ranked.push((c, groups.len() as u32));
+            }
+        }
+        ranked
+    }
+
+    // how much this ballot likes a choice, higher is better, only useful for comparing within a ballot
+    fn preference(&self, choice: usize) -> f32 {
+        match self {
+            CastVotes::Select(v) => if v.contains(&choice) { 1.0 } else { 0.0 },
+            CastVotes::Score(m) => *m.get(&choice).unwrap_or(&0.0),
+            CastVotes::Rank(m) => m.get(&choice).map(|r| -(*r as f32)).unwrap_or(f32::MIN),
+            CastVotes::Grade(m) => *m.get(&choice).unwrap_or(&0) as f32,
+            CastVotes::Points(m) => *m.get(&choice).unwrap_or(&0) as f32,
+        }
+    }
+
+    // credits spent on a quadratic ballot, k votes on a choice cost k*k
+    fn quadratic_cost(&self) -> f32 {
+        match self {
+            CastVotes::Score(m) => m.values().map(|k| k * k).sum(),
+            _ => panic!("Tried to get quadratic cost but don't have a score"),
+        }
+    }
+
+    // if the choice is one of the ballot's favorites
+    // a ballot that marks everything equally favors them all, but leaving everything blank favors none
+    // blank scores and grades were already filled in with the defaults
+    fn is_top_choice(&self, choice: usize, size: usize, ss: &ScoreSettings) -> bool {
+        let marked = |c: usize| match self {
+            CastVotes::Select(v) => v.contains(&c),
+            CastVotes::Rank(m) => m.contains_key(&c),
+            CastVotes::Score(m) => m.get(&c).is_some_and(|s| *s != ss.default_score()),
+            CastVotes::Grade(m) => m.get(&c).is_some_and(|g| *g > 0),
+            CastVotes::Points(m) => m.get(&c).is_some_and(|p| *p > 0),
+        };
+        if let CastVotes::Select(_) = self {
+            return marked(choice);
+        }
+
+        let prefs: Vec<f32> = (0..size).map(|c| self.preference(c)).collect();
+        let best = prefs.iter().cloned().fold(f32::MIN, f32::max);
+        let worst = prefs.iter().cloned().fold(f32::MAX, f32::min);
+        prefs[choice] == best && (best > worst || (0..size).all(marked))
+    }
+
+    fn get_grade_vec(&self) -> Vec<(usize, usize)> {
+        match self {
+            CastVotes::Grade(m) => {
+                let mut v: Vec<(usize, usize)> = Vec::new();
+                for (u, g) in m {
+                    v.push((*u,*g));
+                }
+                v
+            },
+            _ => panic!("Tried to get grade vec but don't have a grade"),
+        }
+    }
+
+    fn are_valid(&self, vt: VoteType, size: usize, ss: &ScoreSettings, sl: &SelectLimits) -> Result<(), String> {
+        let bad_values = format!("invalid values for a {} vote, please fix your vote.\nEach should be a {}", vt.to_string(), vt.value_name(ss));
+        let valid = match vt {
+            vt if vt.is_approval_ballot() => {
+                if let CastVotes::Select(v) = self {
+                    if v.len() < sl.min {
+                        return Err(format!("select at least {} choices, you have {} selected", sl.min, v.len()));
+                    }
+                    if sl.max > 0 && v.len() > sl.max {
+                        return Err(format!("select at most {} choices, you have {} selected", sl.max, v.len()));
+                    }
+                    true
+                } else {
+                    panic!("Tried to check validity of a APPROVAL with out a select backing");
+                }
+            },
+            VOTE_PLURALITY | VOTE_RBALLOT => {
+                // exactly one choice
+                if let CastVotes::Select(v) = self {
+                    if v.len() != 1 {
+                        return Err(format!("select exactly one choice, you have {} selected", v.len()));
+                    }
+                    true
+                } else {
+                    panic!("Tried to check validity of a PLURALITY with out a select backing");
+                }
+            },
+            vt if vt.is_score_ballot() => true,
             VOTE_LSCORE => {
                 // check the sum(abs(scores))
                 let mut abssum: f32 = 0.0f32;
@@ -250,131 +723,1413 @@ impl CastVotes {
                     panic!("Tried to check validity of a LSCORE with no score backing");
                 }
 
-                abssum < 10.0001f32
-            }
-            VOTE_BORDA => {
-                // check each included key has a non-zero rating (default is size)
-                // not too worried about the ratings, just let the sorting sort it
-                if let CastVotes::Rank(m) = self {
-                    for i in 0..size {
-                        if let Some(v) = m.get(&i) {
-                            if *v < 1 {
-                                return false;
-                            }
-                        }
+                if abssum >= ss.budget + 0.0001f32 {
+                    return Err(format!("scores add up to {} (ignoring sign), the budget is {}", abssum, ss.budget));
+                }
+                true
+            }
+            VOTE_QUADRATIC => {
+                let cost = self.quadratic_cost();
+                if cost >= ss.budget + 0.0001f32 {
+                    return Err(format!("votes cost {} credits, only {} to spend", cost, ss.budget));
+                }
+                true
+            }
+            vt if vt.is_ranked() => {
+                // check each ranked choice has a non-zero rank, unranked choices aren't in the map
+                // gaps and shared ranks are fine, the sorting sorts it
+                if let CastVotes::Rank(m) = self {
+                    for i in 0..size {
+                        if let Some(v) = m.get(&i) {
+                            if *v < 1 {
+                                return Err(bad_values);
+                            }
+                        }
+
+                    }
+                } else {
+                    panic!("Tried to check validity of a ranked vote with out a rank backing");
+                }
+                true
+            },
+            VOTE_MJ => {
+                if let CastVotes::Grade(m) = self {
+                    m.values().all(|g| *g < MJ_GRADES.len())
+                } else {
+                    panic!("Tried to check validity of a MJ with out a grade backing");
+                }
+            },
+            VOTE_CUMULATIVE => {
+                if let CastVotes::Points(m) = self {
+                    let used: usize = m.values().sum();
+                    if used > ss.budget as usize {
+                        return Err(format!("used {} points, only {} to spend", used, ss.budget));
+                    }
+                    true
+                } else {
+                    panic!("Tried to check validity of a CUMULATIVE with out a points backing");
+                }
+            },
+            _ => panic!("Tried to check votes with unknown vote type"),
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(bad_values)
+        }
+    }
+
+    fn get_ballot(&self, size: usize, ss: &ScoreSettings) -> Self {
+        match self {
+            CastVotes::Select(v) => {
+                let newv = v.to_vec();
+                CastVotes::Select(newv)
+            },
+            CastVotes::Score(m) => {
+                let mut newm = m.clone();
+                // add in defaults
+                for i in 0..size {
+                    if !newm.contains_key(&i) {
+                        newm.insert(i, ss.default_score());
+                    }
+                }
+                CastVotes::Score(newm)
+            },
+            CastVotes::Rank(m) => {
+                // unranked choices stay unranked, the tallies handle partial ballots
+                CastVotes::Rank(m.clone())
+            },
+            CastVotes::Grade(m) => {
+                let mut newm = m.clone();
+                // ungraded choices get the lowest grade
+                for i in 0..size {
+                    newm.entry(i).or_insert(0);
+                }
+                CastVotes::Grade(newm)
+            },
+            CastVotes::Points(m) => {
+                let mut newm = m.clone();
+                for i in 0..size {
+                    newm.entry(i).or_insert(0);
+                }
+                CastVotes::Points(newm)
+            },
+        }
+    }
+}
+
+struct UserVote {
+    votes: CastVotes,
+    votemsg: MessageId,
+    page: usize,
+}
+
+struct Vote {
+    kind: VoteType,
+    num_winners: usize,
+    borda_variant: BordaVariant,
+    score_settings: ScoreSettings,
+    select_limits: SelectLimits,
+    tie_break: TieBreak,
+    seed: Option<u64>, // drawn when the vote closes, so nobody can work out a draw ahead of time
+    tie_picks: Vec<usize>, // choices the creator picked to break a tie, in order
+    quorum: usize,
+    threshold: f32,
+    none_choice: Option<usize>, // index of the "None of these" choice, if there is one
+    write_ins: bool,
+    vetoes: Vec<(String, usize)>,
+    role_weights: HashMap<RoleId, u64>,
+    time_budget: u32,
+    uservotes: HashMap<UserId,UserVote>,
+    submittedvotes: HashMap<UserId,CastVotes>,
+    weights: HashMap<UserId, u64>, // resolved from roles when a ballot is submitted
+    abstains: HashSet<UserId>,
+}
+
+
+const SPLITMIX_GAMMA: u64 = 0x9E3779B97F4A7C15;
+
+// splitmix64, small and easy to reimplement when checking a draw by hand
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(SPLITMIX_GAMMA);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// Fisher-Yates from the back, so anyone with the seed and the items in the same order gets the same shuffle
+fn seeded_shuffle<T>(items: &mut [T], seed: u64) {
+    let mut state = seed;
+    for i in (1..items.len()).rev() {
+        let j = (splitmix64(&mut state) % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+// draw choices one at a time with chances in proportion to their weights, without putting them back
+// anyone with the seed and the weights in choice order gets the same draw
+fn weighted_draw(weights: &[(usize, f64)], num_winners: usize, seed: u64) -> Vec<(usize, usize)> {
+    let mut state = seed;
+    let mut left: Vec<(usize, f64)> = weights.iter().copied().filter(|(_, w)| w.is_finite() && *w > 0.0).collect();
+    let mut drawn: Vec<(usize, usize)> = Vec::new();
+
+    while drawn.len() < num_winners && !left.is_empty() {
+        let total: f64 = left.iter().map(|(_, w)| w).sum();
+        if !total.is_finite() {
+            break;
+        }
+        // top 53 bits for a float in [0, 1)
+        let mut spot = ((splitmix64(&mut state) >> 11) as f64 / (1u64 << 53) as f64) * total;
+
+        let mut pick = left.len() - 1;
+        for (i, (_, w)) in left.iter().enumerate() {
+            if spot < *w {
+                pick = i;
+                break;
+            }
+            spot -= w;
+        }
+
+        let (c, _) = left.remove(pick);
+        drawn.push((c, drawn.len()));
+    }
+    drawn
+}
+
+// rank choices from counts sorted highest first, keeping any ties for the last winning spot
+fn rank_counts<C: PartialEq + Copy>(counts: &[(usize, C)], num_winners: usize) -> Vec<(usize, usize)> {
+    let mut ranked: Vec<(usize, usize)> = Vec::new();
+    let mut rank = 0;
+    for (i, (c, n)) in counts.iter().enumerate() {
+        if i > 0 && counts[i - 1].1 != *n {
+            if ranked.len() >= num_winners {
+                break;
+            }
+            rank += 1;
+        }
+        ranked.push((*c, rank));
+    }
+    ranked
+}
+
+macro_rules! tally_str {
+    ($tally:expr, $vals:expr, $num_voters:expr) => {
+    {
+        let winners: Vec<(usize, usize)> = $tally.winners().iter().map(|w| (w.candidate, w.rank)).collect();
+        let mut result: String = String::from("\nTotals:\n");
+        for (w, c) in $tally.totals() {
+            result.push_str(&format!("{}: {}\n", c, $vals[w]));
+        }
+        (winners, $num_voters, result)
+    }
+    };
+}
+
+struct RunoffRound {
+    counts: Vec<(usize, f64)>, // first choice counts for the remaining choices, highest first
+    eliminated: Vec<usize>,
+}
+
+// the highest ranked choices on a ballot that are still in the running, more than one if they share a rank
+fn top_group(ballot: &[Vec<usize>], remaining: &[usize]) -> Vec<usize> {
+    ballot.iter()
+        .map(|g| g.iter().copied().filter(|c| remaining.contains(c)).collect::<Vec<usize>>())
+        .find(|g| !g.is_empty())
+        .unwrap_or_default()
+}
+
+// instant runoff, dropping the last place choice(s) each round until one has a majority
+// with more than one winner, keep dropping until only the winners are left
+// a ballot with a tie at the top splits its vote between the tied choices
+fn instant_runoff(ballots: &[Vec<Vec<usize>>], num_choices: usize, num_winners: usize) -> (Vec<(usize, usize)>, Vec<RunoffRound>) {
+    let mut remaining: Vec<usize> = (0..num_choices).collect();
+    let mut rounds: Vec<RunoffRound> = Vec::new();
+
+    loop {
+        let mut counts: HashMap<usize, f64> = remaining.iter().map(|c| (*c, 0.0)).collect();
+        let mut active: f64 = 0.0;
+        for b in ballots {
+            // each ballot goes to its highest ranked choice still in the running
+            let top = top_group(b, &remaining);
+            for c in &top {
+                *counts.get_mut(c).unwrap() += 1.0 / (top.len() as f64);
+            }
+            if !top.is_empty() {
+                active += 1.0;
+            }
+        }
+
+        let mut counts: Vec<(usize, f64)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let top = counts[0].1;
+        let bottom = counts[counts.len() - 1].1;
+
+        let eliminated: Vec<usize> = counts.iter().filter(|(_, n)| *n == bottom).map(|(c, _)| *c).collect();
+
+        // stop on a majority, or when dropping the last place tie would leave too few choices
+        if (num_winners == 1 && top * 2.0 > active) || remaining.len() - eliminated.len() < num_winners {
+            let winners = rank_counts(&counts, num_winners);
+            rounds.push(RunoffRound{counts, eliminated: Vec::new()});
+            return (winners, rounds);
+        }
+
+        remaining.retain(|c| !eliminated.contains(c));
+        rounds.push(RunoffRound{counts, eliminated});
+    }
+}
+
+struct TransferRound {
+    counts: Vec<(usize, f64)>, // current totals for the hopeful choices, highest first
+    elected: Option<(usize, f64)>, // choice and the surplus it passed on
+    eliminated: Option<usize>,
+}
+
+// single transferable vote with a Droop quota
+// surpluses are passed on by scaling down the weight of every ballot that elected the choice
+// a ballot with a tie at the top splits its weight between the tied choices
+fn single_transferable_vote(ballots: &[Vec<Vec<usize>>], num_choices: usize, num_winners: usize) -> (f64, Vec<(usize, usize)>, Vec<TransferRound>) {
+    let quota = ((ballots.len() / (num_winners + 1)) + 1) as f64;
+    let mut weights: Vec<f64> = vec![1.0; ballots.len()];
+    let mut hopeful: Vec<usize> = (0..num_choices).collect();
+    let mut elected: Vec<(usize, usize)> = Vec::new();
+    let mut rounds: Vec<TransferRound> = Vec::new();
+
+    while elected.len() < num_winners && !hopeful.is_empty() {
+        // each ballot goes to its highest ranked choice that is still hopeful
+        let top: Vec<Vec<usize>> = ballots.iter().map(|b| top_group(b, &hopeful)).collect();
+        let mut counts: Vec<(usize, f64)> = hopeful.iter().map(|c| {
+            (*c, top.iter().zip(&weights).filter(|(t, _)| t.contains(c)).map(|(t, w)| w / (t.len() as f64)).sum())
+        }).collect();
+        counts.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        if hopeful.len() + elected.len() <= num_winners {
+            // everyone left gets a seat
+            for (c, _) in &counts {
+                elected.push((*c, elected.len()));
+            }
+            hopeful.clear();
+            rounds.push(TransferRound{counts, elected: None, eliminated: None});
+            break;
+        }
+
+        let (best, best_count) = counts[0];
+        if best_count >= quota {
+            let surplus = best_count - quota;
+            let factor = surplus / best_count;
+            for (t, w) in top.iter().zip(weights.iter_mut()) {
+                if t.contains(&best) {
+                    // only the share that went to the elected choice is scaled down
+                    *w *= 1.0 - ((1.0 - factor) / (t.len() as f64));
+                }
+            }
+            elected.push((best, elected.len()));
+            hopeful.retain(|c| *c != best);
+            rounds.push(TransferRound{counts, elected: Some((best, surplus)), eliminated: None});
+        } else {
+            let (worst, _) = counts[counts.len() - 1];
+            hopeful.retain(|c| *c != worst);
+            rounds.push(TransferRound{counts, elected: None, eliminated: Some(worst)});
+        }
+    }
+
+    (quota, elected, rounds)
+}
+
+// a choice can end with how long it takes, like "Game (90m)" or "Game (1.5h)"
+// gives the name without it, and the length in minutes
+fn parse_duration(choice: &str) -> Option<(&str, u32)> {
+    let (name, len) = choice.trim_end().strip_suffix(')')?.rsplit_once('(')?;
+    let len = len.trim().to_lowercase();
+    let mins = if let Some(h) = len.strip_suffix('h') {
+        h.trim().parse::<f64>().ok()? * 60.0
+    } else {
+        len.strip_suffix("min").or_else(|| len.strip_suffix('m'))?.trim().parse::<f64>().ok()?
+    };
+
+    (1.0..=MAX_BUDGET_HR * 60.0).contains(&mins).then_some((name.trim_end(), mins.round() as u32))
+}
+
+fn minutes_str(mins: u32) -> String {
+    match (mins / 60, mins % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
+// keep choice names short, but don't cut off the length at the end
+fn shorten_choice(choice: &str) -> String {
+    match parse_duration(choice) {
+        // cut by characters, String::truncate panics in the middle of one
+        Some((name, mins)) => {
+            let suffix = format!(" ({}m)", mins);
+            name.chars().take(MAX_CHOICE_LEN - suffix.len()).collect::<String>() + &suffix
+        },
+        None => choice.chars().take(MAX_CHOICE_LEN).collect(),
+    }
+}
+
+// the set of choices with the most utility that fits in the budget, by minutes
+// choices without a length or without any utility are left out
+fn fill_time_budget(utility: &[f64], durations: &[Option<u32>], budget: u32) -> Vec<usize> {
+    let cap = budget as usize;
+    let mut best: Vec<f64> = vec![0.0; cap + 1];
+    // if a choice was used for the best set at each amount of time, to walk back through
+    let mut took: Vec<Vec<bool>> = Vec::new();
+
+    for (u, d) in utility.iter().zip(durations) {
+        let mut row = vec![false; cap + 1];
+        if let Some(d) = d.map(|d| d as usize).filter(|d| *d <= cap && *u > 0.0) {
+            for t in (d..=cap).rev() {
+                if best[t - d] + u > best[t] + 1e-9 {
+                    best[t] = best[t - d] + u;
+                    row[t] = true;
+                }
+            }
+        }
+        took.push(row);
+    }
+
+    let mut t = cap;
+    let mut picked: Vec<usize> = Vec::new();
+    for c in (0..utility.len()).rev() {
+        if took[c][t] {
+            picked.push(c);
+            t -= durations[c].unwrap() as usize;
+        }
+    }
+    picked.reverse();
+    picked
+}
+
+// beyond this many possible sets, PAV gives way to the sequential version
+const PAV_MAX_SETS: usize = 200_000;
+
+// a voter with m approved winners adds 1 + 1/2 + ... + 1/m to a set
+fn pav_score(ballots: &[Vec<usize>], set: &[usize]) -> f64 {
+    ballots.iter().map(|b| {
+        let m = b.iter().filter(|c| set.contains(c)).count();
+        (1..=m).map(|j| 1.0 / (j as f64)).sum::<f64>()
+    }).sum()
+}
+
+fn num_sets(n: usize, k: usize) -> usize {
+    let mut total: usize = 1;
+    for i in 0..k {
+        total = total.saturating_mul(n - i) / (i + 1);
+    }
+    total
+}
+
+// try every set of num_winners choices and keep the first with the best score
+// None if there are too many sets to try
+fn proportional_approval(ballots: &[Vec<usize>], num_choices: usize, num_winners: usize) -> Option<(Vec<usize>, f64)> {
+    let k = num_winners.min(num_choices);
+    if num_sets(num_choices, k) > PAV_MAX_SETS {
+        return None;
+    }
+
+    let mut set: Vec<usize> = (0..k).collect();
+    let mut best: (Vec<usize>, f64) = (set.clone(), pav_score(ballots, &set));
+    loop {
+        // step to the next set in order
+        let mut i = k;
+        while i > 0 && set[i - 1] == num_choices - k + (i - 1) {
+            i -= 1;
+        }
+        if i == 0 {
+            break;
+        }
+        set[i - 1] += 1;
+        for j in i..k {
+            set[j] = set[j - 1] + 1;
+        }
+
+        let score = pav_score(ballots, &set);
+        // a little slack so float noise doesn't pick between equal sets
+        if score > best.1 + 1e-9 {
+            best = (set.clone(), score);
+        }
+    }
+    Some(best)
+}
+
+// add one winner at a time, each voter's approvals counting 1/(1 + winners they already have)
+// returns each pick with the score it won on
+fn sequential_proportional_approval(ballots: &[Vec<usize>], num_choices: usize, num_winners: usize) -> Vec<(usize, f64)> {
+    let mut picks: Vec<(usize, f64)> = Vec::new();
+
+    while picks.len() < num_winners.min(num_choices) {
+        let mut best: Option<(usize, f64)> = None;
+        for c in (0..num_choices).filter(|c| !picks.iter().any(|(p, _)| p == c)) {
+            let score: f64 = ballots.iter().filter(|b| b.contains(&c)).map(|b| {
+                1.0 / ((1 + b.iter().filter(|a| picks.iter().any(|(p, _)| p == *a)).count()) as f64)
+            }).sum();
+            if best.is_none_or(|(_, s)| score > s + 1e-9) {
+                best = Some((c, score));
+            }
+        }
+        picks.push(best.unwrap());
+    }
+    picks
+}
+
+struct StarRunoff {
+    a: usize,
+    b: usize,
+    a_pref: u64,
+    b_pref: u64,
+    winner: usize,
+    tied: Vec<usize>, // choices tied for a runoff spot, if the draw had to pick between them
+}
+
+// total score for each choice, highest first
+fn score_totals(ballots: &[HashMap<usize, f32>], num_choices: usize) -> Vec<(usize, f32)> {
+    let mut totals: Vec<(usize, f32)> = (0..num_choices).map(|c| {
+        (c, ballots.iter().map(|b| b.get(&c).unwrap_or(&0.0)).sum())
+    }).collect();
+    totals.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    totals
+}
+
+// score then automatic runoff between the top two, repeated without the earlier winners for each extra winner
+// choices tied on score for a runoff spot are put in order by a seeded draw, or left in vote order until there is a seed
+fn star_runoffs(ballots: &[HashMap<usize, f32>], totals: &[(usize, f32)], num_winners: usize, seed: Option<u64>) -> (Vec<(usize, usize)>, Vec<StarRunoff>) {
+    let mut winners: Vec<(usize, usize)> = Vec::new();
+    let mut runoffs: Vec<StarRunoff> = Vec::new();
+    while winners.len() < num_winners.min(totals.len()) {
+        let left: Vec<usize> = totals.iter().map(|(c, _)| *c).filter(|c| !winners.iter().any(|(w, _)| w == c)).collect();
+        if left.len() == 1 {
+            winners.push((left[0], winners.len()));
+            break;
+        }
+
+        // the top two by total score face off, each voter backing whichever they scored higher
+        let score = |c: usize| totals.iter().find(|(t, _)| *t == c).unwrap().1;
+        let cut = score(left[1]);
+        let mut finalists: Vec<usize> = left.iter().copied().filter(|c| score(*c) > cut).collect();
+        let mut tied: Vec<usize> = left.iter().copied().filter(|c| score(*c) == cut).collect();
+        if finalists.len() + tied.len() > 2 {
+            // tied choices go in vote order first, so the draw can be redone from the published seed
+            tied.sort();
+            if let Some(seed) = seed {
+                seeded_shuffle(&mut tied, seed);
+            }
+        }
+        finalists.extend(&tied);
+        if finalists.len() <= 2 {
+            tied.clear();
+        }
+        let (a, b) = (finalists[0], finalists[1]);
+        let mut a_pref = 0;
+        let mut b_pref = 0;
+        for ballot in ballots {
+            let sa = ballot.get(&a).unwrap_or(&0.0);
+            let sb = ballot.get(&b).unwrap_or(&0.0);
+            if sa > sb {
+                a_pref += 1;
+            } else if sb > sa {
+                b_pref += 1;
+            }
+        }
+
+        // a tied runoff goes to the higher total score, or the earlier draw
+        let winner = if b_pref > a_pref { b } else { a };
+        winners.push((winner, winners.len()));
+        runoffs.push(StarRunoff{a, b, a_pref, b_pref, winner, tied});
+    }
+
+    (winners, runoffs)
+}
+
+// compare two sorted lists of grades by their median, dropping a median grade from both on a tie
+fn mj_cmp(a: &[usize], b: &[usize]) -> Ordering {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    while !a.is_empty() && !b.is_empty() {
+        // the lower median, so a majority gave at least this grade
+        let am = a[(a.len() - 1) / 2];
+        let bm = b[(b.len() - 1) / 2];
+        if am != bm {
+            return am.cmp(&bm);
+        }
+        a.remove((a.len() - 1) / 2);
+        b.remove((b.len() - 1) / 2);
+    }
+    a.len().cmp(&b.len())
+}
+
+// every choice's sorted grades, with the majority judgment winner first
+fn majority_judgment(ballots: &[Vec<(usize, usize)>], num_choices: usize) -> Vec<(usize, Vec<usize>)> {
+    let mut grades: Vec<(usize, Vec<usize>)> = (0..num_choices).map(|c| {
+        let mut g: Vec<usize> = ballots.iter().flat_map(|b| b.iter().filter(|(u, _)| *u == c).map(|(_, g)| *g)).collect();
+        g.sort();
+        (c, g)
+    }).collect();
+    grades.sort_by(|a, b| mj_cmp(&b.1, &a.1).then(a.0.cmp(&b.0)));
+    grades
+}
+
+fn mj_winners(grades: &[(usize, Vec<usize>)], num_winners: usize) -> Vec<(usize, usize)> {
+    let mut winners: Vec<(usize, usize)> = Vec::new();
+    let mut rank = 0;
+    for (i, (c, g)) in grades.iter().enumerate() {
+        if i > 0 && mj_cmp(&grades[i - 1].1, g) != Ordering::Equal {
+            if winners.len() >= num_winners {
+                break;
+            }
+            rank += 1;
+        }
+        winners.push((*c, rank));
+    }
+    winners
+}
+
+// keep whole lines of the text within max characters, noting how many lines were cut
+fn clip_lines(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.into();
+    }
+
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let mut result: String = String::new();
+    let mut len = 0;
+    for (i, line) in lines.iter().enumerate() {
+        let n = line.chars().count();
+        // leave room for the note
+        if len + n + 50 > max {
+            let note = format!("\n... {} more lines not shown\n", lines.len() - i);
+            if len + note.len() <= max {
+                result.push_str(&note);
+            }
+            break;
+        }
+        result.push_str(line);
+        len += n;
+    }
+    result
+}
+
+// the choice that beats every other choice head to head, if there is one
+fn condorcet_winner(pairs: &HashMap<(usize, usize), u64>, num_choices: usize) -> Option<usize> {
+    (0..num_choices).find(|a| {
+        (0..num_choices).filter(|b| b != a).all(|b| {
+            pairs.get(&(*a, b)).unwrap_or(&0) > pairs.get(&(b, *a)).unwrap_or(&0)
+        })
+    })
+}
+
+// borda points from ranked groups, choices that share a rank split the points for the places they cover
+// unranked choices get nothing
+fn borda_totals(ballots: &[Vec<Vec<usize>>], num_choices: usize, variant: &tallystick::borda::Variant<f64>) -> Vec<(usize, f64)> {
+    let mut totals: Vec<(usize, f64)> = (0..num_choices).map(|c| (c, 0.0)).collect();
+    for b in ballots {
+        let num_marked: usize = b.iter().map(|g| g.len()).sum();
+        let mut pos = 0;
+        for g in b {
+            let points: f64 = (pos..pos + g.len()).map(|p| variant.points(p, num_choices, num_marked)).sum::<f64>() / (g.len() as f64);
+            for c in g {
+                totals[*c].1 += points;
+            }
+            pos += g.len();
+        }
+    }
+    totals.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    totals
+}
+
+// wins minus losses head to head, best first
+fn copeland_scores(pairs: &HashMap<(usize, usize), u64>, num_choices: usize) -> Vec<(usize, i64)> {
+    let mut scores: Vec<(usize, i64)> = (0..num_choices).map(|a| {
+        let score = (0..num_choices).filter(|b| *b != a).map(|b| {
+            let ab = pairs.get(&(a, b)).unwrap_or(&0);
+            let ba = pairs.get(&(b, a)).unwrap_or(&0);
+            match ab.cmp(ba) {
+                Ordering::Greater => 1,
+                Ordering::Less => -1,
+                Ordering::Equal => 0,
+            }
+        }).sum();
+        (a, score)
+    }).collect();
+    scores.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+    scores
+}
+
+// each choice's worst head to head defeat, as the votes against it, smallest first
+fn minimax_scores(pairs: &HashMap<(usize, usize), u64>, num_choices: usize) -> Vec<(usize, u64)> {
+    let mut scores: Vec<(usize, u64)> = (0..num_choices).map(|a| {
+        let worst = (0..num_choices).filter(|b| *b != a).map(|b| {
+            let ab = *pairs.get(&(a, b)).unwrap_or(&0);
+            let ba = *pairs.get(&(b, a)).unwrap_or(&0);
+            if ba > ab { ba } else { 0 }
+        }).max().unwrap_or(0);
+        (a, worst)
+    }).collect();
+    scores.sort_by_key(|(_, n)| *n);
+    scores
+}
+
+// the head to head results, just the ones with a winner in them if there are too many to list
+fn head_to_head_str(pairs: &HashMap<(usize, usize), u64>, vals: &[String], winners: &[(usize, usize)]) -> String {
+    let mut matchups: Vec<(usize, usize)> = Vec::new();
+    for a in 0..vals.len() {
+        for b in (a + 1)..vals.len() {
+            matchups.push((a, b));
+        }
+    }
+
+    let mut result = String::from("\nHead to Head:\n");
+    let total = matchups.len();
+    if total > MAX_PAIR_LINES {
+        matchups.retain(|(a, b)| winners.iter().any(|(w, _)| w == a || w == b));
+        matchups.truncate(MAX_PAIR_LINES);
+        result = String::from("\nHead to Head (winners only):\n");
+    }
+
+    for (a, b) in &matchups {
+        let ab = *pairs.get(&(*a, *b)).unwrap_or(&0);
+        let ba = *pairs.get(&(*b, *a)).unwrap_or(&0);
+        if ab > ba {
+            result.push_str(&format!("{} beats {} by {} ({} to {})\n", vals[*a], vals[*b], ab - ba, ab, ba));
+        } else if ba > ab {
+            result.push_str(&format!("{} beats {} by {} ({} to {})\n", vals[*b], vals[*a], ba - ab, ba, ab));
+        } else {
+            result.push_str(&format!("{} ties {} ({} to {})\n", vals[*a], vals[*b], ab, ba));
+        }
+    }
+    if matchups.len() < total {
+        result.push_str(&format!("... {} more not shown\n", total - matchups.len()));
+    }
+    result
+}
+
+struct PairResult {
+    winner: usize,
+    loser: usize,
+    support: u64,
+    opposition: u64,
+}
+
+// Tideman's ranked pairs, locking in the strongest head to head wins first and skipping any that would make a cycle
+fn ranked_pairs(pairs: &HashMap<(usize, usize), u64>, num_choices: usize, num_winners: usize) -> (Vec<(usize, usize)>, Vec<PairResult>, Vec<PairResult>) {
+    let mut majorities: Vec<PairResult> = Vec::new();
+    for a in 0..num_choices {
+        for b in 0..num_choices {
+            let ab = *pairs.get(&(a, b)).unwrap_or(&0);
+            let ba = *pairs.get(&(b, a)).unwrap_or(&0);
+            if ab > ba {
+                majorities.push(PairResult{winner: a, loser: b, support: ab, opposition: ba});
+            }
+        }
+    }
+    majorities.sort_by(|x, y| {
+        y.support.cmp(&x.support)
+            .then(x.opposition.cmp(&y.opposition))
+            .then((x.winner, x.loser).cmp(&(y.winner, y.loser)))
+    });
+
+    let mut locked: Vec<PairResult> = Vec::new();
+    let mut skipped: Vec<PairResult> = Vec::new();
+    for pr in majorities {
+        // would locking winner -> loser let us get from the loser back to the winner?
+        let mut seen: Vec<usize> = vec![pr.loser];
+        let mut stack: Vec<usize> = vec![pr.loser];
+        while let Some(c) = stack.pop() {
+            for l in locked.iter().filter(|l| l.winner == c) {
+                if !seen.contains(&l.loser) {
+                    seen.push(l.loser);
+                    stack.push(l.loser);
+                }
+            }
+        }
+
+        if seen.contains(&pr.winner) {
+            skipped.push(pr);
+        } else {
+            locked.push(pr);
+        }
+    }
+
+    // the winners are the choices nothing left is locked in over, then take them out and repeat for the next rank
+    let mut left: Vec<usize> = (0..num_choices).collect();
+    let mut winners: Vec<(usize, usize)> = Vec::new();
+    let mut rank = 0;
+    while !left.is_empty() && winners.len() < num_winners {
+        let sources: Vec<usize> = left.iter().copied().filter(|c| {
+            !locked.iter().any(|l| l.loser == *c && left.contains(&l.winner))
+        }).collect();
+        left.retain(|c| !sources.contains(c));
+        winners.extend(sources.into_iter().map(|c| (c, rank)));
+        rank += 1;
+    }
+    (winners, locked, skipped)
+}
+
+impl Vote {
+    fn new(vi: &VoteInfo) -> Self {
+        Vote {
+            kind: vi.kind,
+            num_winners: vi.num_winners,
+            borda_variant: vi.borda_variant,
+            score_settings: vi.score_settings,
+            select_limits: vi.select_limits,
+            tie_break: vi.tie_break,
+            seed: None,
+            tie_picks: Vec::new(),
+            quorum: vi.quorum,
+            threshold: vi.threshold,
+            none_choice: if vi.none_option { vi.vals.iter().position(|v| v == NONE_CHOICE) } else { None },
+            write_ins: vi.write_ins,
+            vetoes: vi.vetoes.clone(),
+            role_weights: vi.role_weights.clone(),
+            time_budget: vi.time_budget,
+            uservotes: HashMap::new(),
+            submittedvotes: HashMap::new(),
+            weights: HashMap::new(),
+            abstains: HashSet::new(),
+        }
+    }
+
+    // pick the seed for any random draws, once voting is over
+    fn close(&mut self) {
+        if self.seed.is_none() {
+            self.seed = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64 % 1_000_000_000);
+        }
+    }
+
+    fn get_results(&self, vals: &[String], extra: &str) -> String {
+        let (winners, num_voters, details) = self.tally(vals, self.num_winners);
+        let (winners, note) = self.break_ties(winners, vals);
+
+        let mut result: String = if self.kind.is_time_budget() {
+            let total: u32 = winners.iter().filter_map(|(w, _)| parse_duration(&vals[*w])).map(|(_, m)| m).sum();
+            let mut result: String = format!("{} Vote Results (with {} voters{}):\nPicked {} of {}:\n", self.kind.to_string(), num_voters, self.get_weighted_str(), minutes_str(total), minutes_str(self.time_budget));
+            for (w, _) in &winners {
+                result.push_str(&format!("{}\n", vals[*w]));
+            }
+            result
+        } else if self.num_winners == 1 {
+            let mut result: String = format!("{} Vote Results (with {} voters{}):\nWinner:\n", self.kind.to_string(), num_voters, self.get_weighted_str());
+            for (w, _) in &winners {
+                result.push_str(&format!("{}\n", vals[*w]));
+            }
+            result
+        } else {
+            let mut result: String = format!("{} Vote Results (with {} voters{}):\nTop {} Winners:\n", self.kind.to_string(), num_voters, self.get_weighted_str(), self.num_winners);
+            for (w, rank) in &winners {
+                result.push_str(&format!("{}. {}\n", rank + 1, vals[*w]));
+            }
+            result
+        };
+        if !self.abstains.is_empty() {
+            result.push_str(&format!("{} abstained\n", self.abstains.len()));
+        }
+        if let Some(nc) = self.none_choice {
+            if let Some((_, rank)) = winners.iter().find(|(c, _)| *c == nc) {
+                if *rank == 0 {
+                    result.push_str(&format!("\nNo game was picked, \"{}\" won\n", NONE_CHOICE));
+                } else {
+                    result.push_str(&format!("\nOnly the choices ranked above \"{}\" are picked\n", NONE_CHOICE));
+                }
+            }
+        }
+        result.push_str(&note);
+
+        let mut more: String = String::new();
+        if !self.vetoes.is_empty() {
+            more.push_str("\nVetoed:\n");
+            for (v, n) in &self.vetoes {
+                more.push_str(&format!("{} ({} {})\n", v, n, if *n == 1 { "veto" } else { "vetoes" }));
+            }
+        }
+        more.push_str(&details);
+
+        // the details give way first if it won't all fit in a message
+        let room = MAX_MSG_LEN.saturating_sub(RESULTS_MARGIN + result.chars().count() + extra.chars().count());
+        result.push_str(&clip_lines(&more, room));
+        result.push_str(extra);
+        result
+    }
+
+    // the weight for a member's ballot, from the highest weighted role they have
+    fn resolve_weight(&self, member: Option<&Member>) -> u64 {
+        match member {
+            Some(m) => m.roles.iter().filter_map(|r| self.role_weights.get(r)).copied().max().unwrap_or(1),
+            None => 1,
+        }
+    }
+
+    fn get_weight(&self, uid: &UserId) -> u64 {
+        *self.weights.get(uid).unwrap_or(&1)
+    }
+
+    fn get_weighted_str(&self) -> String {
+        let weighted: u64 = self.submittedvotes.keys().map(|u| self.get_weight(u)).sum();
+        if weighted == self.submittedvotes.len() as u64 {
+            String::new()
+        } else {
+            format!(", {} weighted", weighted)
+        }
+    }
+
+    // our own tallies don't take weights, so each ballot goes in once per point of weight
+    fn weighted_ballots<T: Clone>(&self, f: impl Fn(&CastVotes) -> T) -> Vec<T> {
+        let mut ballots: Vec<T> = Vec::new();
+        for (uid, cv) in &self.submittedvotes {
+            let b = f(cv);
+            for _ in 0..self.get_weight(uid) {
+                ballots.push(b.clone());
+            }
+        }
+        ballots
+    }
+
+    // head to head totals from the ranked ballots, (a, b) is how many put a over b
+    fn pairwise_totals(&self, num_choices: usize) -> HashMap<(usize, usize), u64> {
+        let mut tally = DefaultCondorcetTally::with_candidates(1, (0..num_choices).collect());
+
+        for (uid, cv) in &self.submittedvotes {
+            tally.ranked_add_weighted(&cv.get_ranked_vec(num_choices), self.get_weight(uid)).unwrap();
+        }
+
+        tally.totals().into_iter().collect()
+    }
+
+    // say if a ranked method's winner is the same as Borda would pick from the same ballots
+    fn borda_compare_str(&self, winners: &[(usize, usize)], vals: &[String]) -> String {
+        let ballots: Vec<Vec<Vec<usize>>> = self.weighted_ballots(|cv| cv.get_rank_groups());
+        let totals = borda_totals(&ballots, vals.len(), &self.borda_variant.get_variant());
+
+        let mut borda: Vec<usize> = rank_counts(&totals, 1).iter().filter(|(_, r)| *r == 0).map(|(c, _)| *c).collect();
+        let mut ours: Vec<usize> = winners.iter().filter(|(_, r)| *r == 0).map(|(c, _)| *c).collect();
+        borda.sort();
+        ours.sort();
+
+        let names: Vec<&str> = borda.iter().map(|c| &vals[*c][..]).collect();
+        if borda == ours {
+            format!("\nSame winner as Borda ({})\n", names.join(", "))
+        } else {
+            format!("\nDifferent from Borda, which picks {}\n", names.join(", "))
+        }
+    }
+
+    // how many of the winners each voter approved of
+    fn approved_winners_str(&self, winners: &[usize]) -> String {
+        let mut counts: Vec<usize> = vec![0; winners.len() + 1];
+        for cv in self.submittedvotes.values() {
+            counts[cv.get_vote_vec().iter().filter(|c| winners.contains(c)).count()] += 1;
+        }
+
+        let mut result: String = String::from("\nApproved winners per voter:\n");
+        for (m, n) in counts.iter().enumerate().rev() {
+            result.push_str(&format!("{} voters got {} of their choices\n", n, m));
+        }
+        result
+    }
+
+    // draw the lottery winners, with the chances each choice had and the seed to redo the draw
+    fn lottery(&self, chances: &[(usize, f64)], vals: &[String], num_winners: usize) -> (Vec<(usize, usize)>, usize, String) {
+        let total: f64 = chances.iter().map(|(_, w)| w).sum();
+        if !total.is_finite() || total <= 0.0 {
+            return (Vec::new(), self.submittedvotes.len(), String::from("\nNothing to draw from, no choice had any chance\n"));
+        }
 
-                    }
+        let mut sorted: Vec<(usize, f64)> = chances.to_vec();
+        sorted.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let mut result: String = match self.seed {
+            Some(seed) => format!("\nDrawn at random (seed {})\nChances:\n", seed),
+            None => String::from("\nDrawn at random when the vote closes\nChances:\n"),
+        };
+        for (c, w) in &sorted {
+            result.push_str(&format!("{:.1}% ({}): {}\n", 100.0 * w / total, w, vals[*c]));
+        }
+
+        // nothing is drawn until the seed is
+        let winners = match self.seed {
+            Some(seed) => weighted_draw(chances, num_winners, seed),
+            None => Vec::new(),
+        };
+        (winners, self.submittedvotes.len(), result)
+    }
+
+    // a running note for the top of a ballot, so limits are seen before submitting
+    fn ballot_status(&self, cv: &CastVotes) -> String {
+        match cv {
+            CastVotes::Select(v) if self.kind.is_approval_ballot() && self.select_limits.is_limited() => {
+                if self.select_limits.max > 0 {
+                    format!("\n{}/{} selected", v.len(), self.select_limits.max)
                 } else {
-                    panic!("Tried to check validity of a BORDA with out a rank backing");
+                    format!("\n{} selected (at least {})", v.len(), self.select_limits.min)
                 }
-                true
             },
-            _ => panic!("Tried to check votes with unknown vote type"),
+            CastVotes::Points(m) => format!("\n{} points left", self.points_left(m)),
+            CastVotes::Score(_) if self.kind == VOTE_QUADRATIC => {
+                format!("\n{}/{} credits used", cv.quadratic_cost(), self.score_settings.budget)
+            },
+            _ => String::new(),
         }
     }
 
-    fn get_ballot(&self, size: usize) -> Self {
-        match self {
-            CastVotes::Select(v) => {
-                let newv = v.to_vec();
-                CastVotes::Select(newv)
-            },
-            CastVotes::Score(m) => {
-                let mut newm = m.clone();
-                // add in defaults
-                for i in 0..size {
-                    if !newm.contains_key(&i) {
-                        newm.insert(i, 0.0);
-                    }
-                }
-                CastVotes::Score(newm)
-            },
-            CastVotes::Rank(m) => {
-                let mut newm = m.clone();
-                // add in defaults
-                for i in 0..size {
-                    if !newm.contains_key(&i) {
-                        newm.insert(i, size);
-                    }
-                }
-                CastVotes::Rank(newm)
-            }
+    fn points_left(&self, m: &HashMap<usize, usize>) -> usize {
+        (self.score_settings.budget as usize).saturating_sub(m.values().sum())
+    }
+
+    // if "None of these" took first place
+    fn none_won(&self, vals: &[String]) -> bool {
+        if let Some(nc) = self.none_choice {
+            let (winners, _, _) = self.tally(vals, self.num_winners);
+            let (winners, _) = self.break_ties(winners, vals);
+            winners.contains(&(nc, 0))
+        } else {
+            false
         }
     }
-}
 
-struct UserVote {
-    votes: CastVotes,
-    votemsg: MessageId,
-    page: usize,
-}
+    // why the vote can't be decided, if the quorum or threshold wasn't met
+    fn check_decision(&self, vals: &[String]) -> Option<String> {
+        // abstaining still counts toward the quorum
+        let num_participants = self.submittedvotes.len() + self.abstains.len();
+        if num_participants < self.quorum {
+            return Some(format!("Only {} voters, needed at least {}", num_participants, self.quorum));
+        }
 
-struct Vote {
-    kind: VoteType,
-    uservotes: HashMap<UserId,UserVote>,
-    submittedvotes: HashMap<UserId,CastVotes>,
-}
+        // weighted the same way as the tally that picked the winners
+        let num_voters: u64 = self.submittedvotes.keys().map(|u| self.get_weight(u)).sum();
+
+        if self.threshold > 0.0 {
+            let (winners, _, _) = self.tally(vals, self.num_winners);
+            let (winners, _) = self.break_ties(winners, vals);
+
+            let mut reasons: Vec<String> = Vec::new();
+            for (w, _) in winners {
+                let support: u64 = self.submittedvotes.iter()
+                    .filter(|(_, cv)| cv.is_top_choice(w, vals.len(), &self.score_settings))
+                    .map(|(u, _)| self.get_weight(u))
+                    .sum();
+                let percent = 100.0 * (support as f32) / (num_voters.max(1) as f32);
+                if percent < self.threshold {
+                    reasons.push(format!("{} was a top choice for {:.0}% of voters, needed {}%", vals[w], percent, self.threshold));
+                }
+            }
+            if !reasons.is_empty() {
+                return Some(reasons.join("\n"));
+            }
+        }
 
+        None
+    }
 
-macro_rules! tally_str {
-    ($tally:expr, $vals:expr, $votetype:expr, $num_voters:expr, $extra:expr) => {
-    {
-        let mut result: String = format!("{} Vote Results (with {} voters):\nWinner:\n", $votetype.to_string(), $num_voters);
-        let winners = $tally.winners().all();
-        for w in winners {
-            result.push_str(&format!("{}\n", $vals[w]));
+    // the choices still tied for the last winning spot, after the tie break
+    fn get_tie(&self, vals: &[String]) -> Option<Vec<usize>> {
+        let (winners, _, _) = self.tally(vals, self.num_winners);
+        let (winners, _) = self.break_ties(winners, vals);
+
+        if winners.len() <= self.num_winners || self.kind.is_time_budget() {
+            return None;
         }
-        result.push_str("\nTotals:\n");
-        for (w, c) in $tally.totals() {
-            result.push_str(&format!("{}: {}\n", c, $vals[w]));
+        let last = winners.iter().map(|(_, r)| *r).max().unwrap();
+        Some(winners.iter().filter(|(_, r)| *r == last).map(|(c, _)| *c).collect())
+    }
+
+    // the choices for a runoff vote, either the ones in a tie or the winners and the next in line
+    fn get_runoff_choices(&self, vals: &[String]) -> Vec<usize> {
+        let (winners, _, _) = self.tally(vals, self.num_winners);
+        if winners.len() > self.num_winners {
+            return winners.iter().map(|(c, _)| *c).collect();
         }
-        result.push_str($extra);
-        result
+
+        let (leaders, _, _) = self.tally(vals, self.num_winners + 1);
+        leaders.iter().map(|(c, _)| *c).collect()
     }
-    };
-}
 
-impl Vote {
-    fn new(vt: VoteType) -> Self {
-        Vote {
-            kind: vt,
-            uservotes: HashMap::new(),
-            submittedvotes: HashMap::new(),
+    // narrow down a tie for the last winning spot, giving the new ranking and a note on how it was done
+    fn break_ties(&self, mut winners: Vec<(usize, usize)>, vals: &[String]) -> (Vec<(usize, usize)>, String) {
+        // a time budget picks a whole set, there is no last spot to be tied for
+        if winners.len() <= self.num_winners || self.tie_break == TieBreak::ShowAll || self.kind.is_time_budget() {
+            return (winners, String::new());
+        }
+
+        let last = winners.iter().map(|(_, r)| *r).max().unwrap();
+        // tied choices go in vote order, so the seeded draw can be redone from the published seed
+        let mut tied: Vec<usize> = winners.iter().filter(|(_, r)| *r == last).map(|(c, _)| *c).collect();
+        tied.sort();
+        winners.retain(|(_, r)| *r != last);
+        let needed = self.num_winners - winners.len();
+
+        // anything that draws has to wait for the seed
+        let draws = match self.tie_break {
+            TieBreak::Random | TieBreak::Borda => true,
+            TieBreak::Creator => tied.len() > MAX_TIE_BTNS,
+            _ => false,
+        };
+        let seed = match self.seed {
+            Some(seed) => seed,
+            None if draws => {
+                winners.extend(tied.into_iter().map(|c| (c, last)));
+                return (winners, format!("\nTied, broken by {} when the vote closes\n", self.tie_break));
+            },
+            None => 0,
+        };
+
+        let note = match self.tie_break {
+            TieBreak::Random => {
+                seeded_shuffle(&mut tied, seed);
+                format!("\nTie broken by random draw (seed {})\n", seed)
+            },
+            TieBreak::Borda => {
+                // points for each tied choice a ballot puts above another tied choice
+                // random order first, so anything still tied is left to the draw
+                seeded_shuffle(&mut tied, seed);
+                let points: HashMap<usize, u64> = tied.iter().map(|a| {
+                    (*a, self.submittedvotes.iter().map(|(uid, cv)| {
+                        (tied.iter().filter(|b| cv.preference(*a) > cv.preference(**b)).count() as u64) * self.get_weight(uid)
+                    }).sum())
+                }).collect();
+                tied.sort_by(|a, b| points[b].cmp(&points[a]));
+
+                let pstr: Vec<String> = tied.iter().map(|c| format!("{} {}", vals[*c], points[c])).collect();
+                format!("\nTie broken by Borda count among the tied choices ({}), then random draw (seed {})\n", pstr.join(", "), seed)
+            },
+            TieBreak::Creator if tied.len() > MAX_TIE_BTNS => {
+                // too many to give the creator a button for each
+                seeded_shuffle(&mut tied, seed);
+                format!("\nToo many choices tied for the vote creator to pick, tie broken by random draw (seed {})\n", seed)
+            },
+            TieBreak::Creator => {
+                let picks: Vec<usize> = self.tie_picks.iter().copied().filter(|c| tied.contains(c)).take(needed).collect();
+                if picks.len() < needed {
+                    // still waiting on the creator, the rest stay tied after what has been picked
+                    tied.retain(|c| !picks.contains(c));
+                    for (i, c) in picks.iter().enumerate() {
+                        winners.push((*c, last + i));
+                    }
+                    for c in tied {
+                        winners.push((c, last + picks.len()));
+                    }
+                    return (winners, String::from("\nTied, waiting for the vote creator to pick\n"));
+                }
+                tied = picks;
+                String::from("\nTie broken by the vote creator\n")
+            },
+            TieBreak::Runoff => {
+                // leave the tie for the runoff
+                winners.extend(tied.into_iter().map(|c| (c, last)));
+                return (winners, String::from("\nTied, a runoff vote will start when this one ends\n"));
+            },
+            TieBreak::ShowAll => unreachable!(),
+        };
+
+        for (i, c) in tied.into_iter().take(needed).enumerate() {
+            winners.push((c, last + i));
         }
+        (winners, note)
     }
 
-    fn get_results(&self, vals: &Vec<String>, extra: &str) -> String {
+    // the ranked winners, number of voters, and the details of the count for this vote type
+    fn tally(&self, vals: &[String], num_winners: usize) -> (Vec<(usize, usize)>, usize, String) {
         let mut num_voters = 0;
         match self.kind {
             VOTE_APPROVAL => {
-                let mut tally = DefaultApprovalTally::new(1);
+                let mut tally = DefaultApprovalTally::new(num_winners);
+
+                for (uid, cv) in &self.submittedvotes {
+                    tally.add_weighted(cv.get_vote_vec(), self.get_weight(uid));
+                    num_voters += 1;
+                }
 
-                for (_, cv) in &self.submittedvotes {
-                    tally.add(cv.get_vote_vec());
+                tally_str!(tally, vals, num_voters)
+            },
+            VOTE_PLURALITY => {
+                let mut tally = DefaultPluralityTally::new(num_winners);
+
+                for (uid, cv) in &self.submittedvotes {
+                    for c in cv.get_vote_vec() {
+                        tally.add_weighted(c, self.get_weight(uid));
+                    }
                     num_voters += 1;
                 }
 
-                tally_str!(tally, vals, self.kind, num_voters, extra)
+                tally_str!(tally, vals, num_voters)
             },
-            VOTE_SCORE | VOTE_LSCORE => {
-                let mut tally = ScoreTally::<usize, f32>::new(1);
+            VOTE_SCORE | VOTE_LSCORE | VOTE_CUMULATIVE | VOTE_QUADRATIC => {
+                let mut tally = ScoreTally::<usize, f32>::new(num_winners);
 
-                for (_, cv) in &self.submittedvotes {
-                    tally.add(cv.get_vote_weight_vec());
+                for (uid, cv) in &self.submittedvotes {
+                    tally.add_weighted(cv.get_vote_weight_vec(), self.get_weight(uid) as f32);
                     num_voters += 1;
                 }
 
-                tally_str!(tally, vals, self.kind, num_voters, extra)
+                tally_str!(tally, vals, num_voters)
             },
             VOTE_BORDA => {
-                let mut tally = DefaultBordaTally::new(1, tallystick::borda::Variant::Borda);
+                let ballots: Vec<Vec<Vec<usize>>> = self.weighted_ballots(|cv| cv.get_rank_groups());
+                let totals = borda_totals(&ballots, vals.len(), &self.borda_variant.get_variant());
+                let winners = rank_counts(&totals, num_winners);
+
+                let mut result: String = String::from("\nTotals:\n");
+                for (c, n) in &totals {
+                    result.push_str(&format!("{}: {}\n", n, vals[*c]));
+                }
+                (winners, self.submittedvotes.len(), result)
+            },
+            VOTE_IRV => {
+                let ballots: Vec<Vec<Vec<usize>>> = self.weighted_ballots(|cv| cv.get_rank_groups());
+                let (winners, rounds) = instant_runoff(&ballots, vals.len(), num_winners);
+
+                // every count only for the first and last rounds, so long votes still fit in a message
+                let mut result: String = String::new();
+                for (i, round) in rounds.iter().enumerate() {
+                    result.push_str(&format!("\nRound {}:\n", i + 1));
+                    if i == 0 || i == rounds.len() - 1 {
+                        for (c, n) in &round.counts {
+                            result.push_str(&format!("{}: {}\n", n, vals[*c]));
+                        }
+                    }
+                    for c in &round.eliminated {
+                        let n = round.counts.iter().find(|(rc, _)| rc == c).map(|(_, n)| *n).unwrap_or(0.0);
+                        result.push_str(&format!("Eliminated: {} with {}\n", vals[*c], n));
+                    }
+                }
+                (winners, self.submittedvotes.len(), result)
+            },
+            VOTE_SCHULZE => {
+                let mut tally = DefaultSchulzeTally::with_candidates(num_winners, tallystick::schulze::Variant::Winning, (0..vals.len()).collect());
 
-                for (_, cv) in &self.submittedvotes {
-                    tally.add(cv.get_vote_vec()).unwrap();
+                for (uid, cv) in &self.submittedvotes {
+                    tally.ranked_add_weighted(&cv.get_ranked_vec(vals.len()), self.get_weight(uid)).unwrap();
                     num_voters += 1;
                 }
 
-                tally_str!(tally, vals, self.kind, num_voters, extra)
+                let winners: Vec<(usize, usize)> = tally.winners().iter().map(|w| (w.candidate, w.rank)).collect();
+                let mut result: String = String::new();
+
+                let pairs: HashMap<(usize, usize), u64> = tally.totals().into_iter().collect();
+                match condorcet_winner(&pairs, vals.len()) {
+                    Some(w) => result.push_str(&format!("\n{} is the Condorcet winner, beating every other choice head to head\n", vals[w])),
+                    None => result.push_str("\nNo Condorcet winner, the cycle was resolved by the strongest paths\n"),
+                };
+                result.push_str(&head_to_head_str(&pairs, vals, &winners));
+                (winners, num_voters, result)
+            },
+            VOTE_RPAIRS => {
+                let pairs = self.pairwise_totals(vals.len());
+                num_voters = self.submittedvotes.len();
+                let (winners, locked, skipped) = ranked_pairs(&pairs, vals.len(), num_winners);
+
+                // the strongest pairs decide the most, so the list is cut from the end
+                let pairs_str = |prs: &[PairResult], max: usize| {
+                    let mut s: String = String::new();
+                    for pr in prs.iter().take(max) {
+                        s.push_str(&format!("{} over {} ({} to {})\n", vals[pr.winner], vals[pr.loser], pr.support, pr.opposition));
+                    }
+                    if prs.len() > max {
+                        s.push_str(&format!("... {} more not shown\n", prs.len() - max));
+                    }
+                    s
+                };
+
+                let mut result: String = String::from("\nLocked Pairs:\n");
+                result.push_str(&pairs_str(&locked, MAX_PAIR_LINES));
+                if !skipped.is_empty() {
+                    result.push_str("\nSkipped Pairs (would create a cycle):\n");
+                    result.push_str(&pairs_str(&skipped, MAX_PAIR_LINES / 2));
+                }
+                (winners, num_voters, result)
+            },
+            VOTE_COPELAND => {
+                let pairs = self.pairwise_totals(vals.len());
+                let scores = copeland_scores(&pairs, vals.len());
+                let winners = rank_counts(&scores, num_winners);
+
+                let mut result: String = self.borda_compare_str(&winners, vals);
+                result.push_str("\nHead to Head Wins minus Losses:\n");
+                for (c, n) in &scores {
+                    result.push_str(&format!("{}: {}\n", n, vals[*c]));
+                }
+                result.push_str(&head_to_head_str(&pairs, vals, &winners));
+                (winners, self.submittedvotes.len(), result)
+            },
+            VOTE_MINIMAX => {
+                let pairs = self.pairwise_totals(vals.len());
+                let scores = minimax_scores(&pairs, vals.len());
+                let winners = rank_counts(&scores, num_winners);
+
+                let mut result: String = self.borda_compare_str(&winners, vals);
+                result.push_str("\nVotes Against in Worst Head to Head Loss:\n");
+                for (c, n) in &scores {
+                    result.push_str(&format!("{}: {}\n", n, vals[*c]));
+                }
+                result.push_str(&head_to_head_str(&pairs, vals, &winners));
+                (winners, self.submittedvotes.len(), result)
+            },
+            VOTE_PAV | VOTE_SPAV => {
+                let ballots: Vec<Vec<usize>> = self.weighted_ballots(|cv| cv.get_vote_vec());
+
+                let mut result: String = String::new();
+                let exact = if self.kind == VOTE_PAV {
+                    let found = proportional_approval(&ballots, vals.len(), num_winners);
+                    if found.is_none() {
+                        result.push_str("\nToo many possible sets to check them all, picked one winner at a time instead\n");
+                    }
+                    found
+                } else {
+                    None
+                };
+
+                let picked: Vec<usize> = match exact {
+                    Some((set, score)) => {
+                        result.push_str(&format!("\nBest set scored {:.2}\n", score));
+                        // show the set highest approved first
+                        let mut set: Vec<(usize, usize)> = set.into_iter().map(|c| {
+                            (c, ballots.iter().filter(|b| b.contains(&c)).count())
+                        }).collect();
+                        set.sort_by_key(|(c, n)| (std::cmp::Reverse(*n), *c));
+                        for (c, n) in &set {
+                            result.push_str(&format!("{}: {}\n", n, vals[*c]));
+                        }
+                        set.into_iter().map(|(c, _)| c).collect()
+                    },
+                    None => {
+                        let picks = sequential_proportional_approval(&ballots, vals.len(), num_winners);
+                        result.push_str("\nRounds:\n");
+                        for (i, (c, score)) in picks.iter().enumerate() {
+                            result.push_str(&format!("{}. {} with {:.2}\n", i + 1, vals[*c], score));
+                        }
+                        picks.into_iter().map(|(c, _)| c).collect()
+                    },
+                };
+
+                result.push_str(&self.approved_winners_str(&picked));
+                let winners = picked.into_iter().enumerate().map(|(r, c)| (c, r)).collect();
+                (winners, self.submittedvotes.len(), result)
+            },
+            VOTE_TAPPROVAL | VOTE_TSCORE => {
+                // how much the voters want each choice, the lowest score counts as nothing
+                let mut utility: Vec<f64> = vec![0.0; vals.len()];
+                for (uid, cv) in &self.submittedvotes {
+                    let w = self.get_weight(uid) as f64;
+                    if self.kind == VOTE_TAPPROVAL {
+                        for c in cv.get_vote_vec() {
+                            utility[c] += w;
+                        }
+                    } else {
+                        for (c, score) in cv.get_vote_weight_vec() {
+                            utility[c] += ((score - self.score_settings.min) as f64) * w;
+                        }
+                    }
+                }
+                let durations: Vec<Option<u32>> = vals.iter().map(|v| parse_duration(v).map(|(_, m)| m)).collect();
+                let picked = fill_time_budget(&utility, &durations, self.time_budget);
+
+                let mut sorted: Vec<usize> = (0..vals.len()).collect();
+                sorted.sort_by(|a, b| utility[*b].total_cmp(&utility[*a]).then(a.cmp(b)));
+
+                let mut result: String = String::from("\nUtility:\n");
+                for c in &sorted {
+                    if durations[*c].is_some() {
+                        result.push_str(&format!("{}: {}\n", utility[*c], vals[*c]));
+                    } else {
+                        result.push_str(&format!("{}: {} (no length given, left out)\n", utility[*c], vals[*c]));
+                    }
+                }
+                let total: f64 = picked.iter().map(|c| utility[*c]).sum();
+                result.push_str(&format!("Picked set has {} utility\n", total));
+
+                // the picked set, most wanted first
+                let winners = sorted.into_iter().filter(|c| picked.contains(c)).enumerate().map(|(r, c)| (c, r)).collect();
+                (winners, self.submittedvotes.len(), result)
+            },
+            VOTE_RBALLOT | VOTE_ALOTTERY => {
+                // drawing a random ballot is the same as drawing a choice by how many ballots picked it
+                let mut chances: Vec<(usize, f64)> = (0..vals.len()).map(|c| (c, 0.0)).collect();
+                for (uid, cv) in &self.submittedvotes {
+                    for c in cv.get_vote_vec() {
+                        chances[c].1 += self.get_weight(uid) as f64;
+                    }
+                }
+
+                self.lottery(&chances, vals, num_winners)
+            },
+            VOTE_SLOTTERY => {
+                // scores are shifted up so the lowest score is no chance at all
+                let mut chances: Vec<(usize, f64)> = (0..vals.len()).map(|c| (c, 0.0)).collect();
+                for (uid, cv) in &self.submittedvotes {
+                    for (c, score) in cv.get_vote_weight_vec() {
+                        chances[c].1 += ((score - self.score_settings.min) as f64) * (self.get_weight(uid) as f64);
+                    }
+                }
+
+                self.lottery(&chances, vals, num_winners)
+            },
+            VOTE_STV => {
+                let ballots: Vec<Vec<Vec<usize>>> = self.weighted_ballots(|cv| cv.get_rank_groups());
+                let (quota, winners, rounds) = single_transferable_vote(&ballots, vals.len(), num_winners);
+
+                // every count only for the first and last rounds, so long votes still fit in a message
+                let mut result: String = format!("\nQuota: {}\n", quota);
+                for (i, round) in rounds.iter().enumerate() {
+                    result.push_str(&format!("\nRound {}:\n", i + 1));
+                    if i == 0 || i == rounds.len() - 1 {
+                        for (c, n) in &round.counts {
+                            result.push_str(&format!("{:.2}: {}\n", n, vals[*c]));
+                        }
+                    }
+                    let count = |c: usize| round.counts.iter().find(|(rc, _)| *rc == c).map(|(_, n)| *n).unwrap_or(0.0);
+                    if let Some((c, surplus)) = round.elected {
+                        result.push_str(&format!("Elected: {} with {:.2} (surplus of {:.2} transferred)\n", vals[c], count(c), surplus));
+                    }
+                    if let Some(c) = round.eliminated {
+                        result.push_str(&format!("Eliminated: {} with {:.2}\n", vals[c], count(c)));
+                    }
+                }
+                (winners, self.submittedvotes.len(), result)
+            },
+            VOTE_STAR => {
+                let ballots: Vec<HashMap<usize, f32>> = self.weighted_ballots(|cv| {
+                    cv.get_vote_weight_vec().into_iter().collect()
+                });
+                let totals = score_totals(&ballots, vals.len());
+                let (winners, runoffs) = star_runoffs(&ballots, &totals, num_winners, self.seed);
+
+                let mut result: String = String::from("\nScoring Round:\n");
+                for (c, n) in &totals {
+                    result.push_str(&format!("{}: {}\n", n, vals[*c]));
+                }
+                for ro in &runoffs {
+                    if !ro.tied.is_empty() {
+                        let names: Vec<&str> = ro.tied.iter().map(|c| &vals[*c][..]).collect();
+                        result.push_str(&match self.seed {
+                            Some(seed) => format!("\n{} tied for a runoff spot, drawn at random (seed {})\n", names.join(", "), seed),
+                            None => format!("\n{} tied for a runoff spot, drawn at random when the vote closes\n", names.join(", ")),
+                        });
+                    }
+                    result.push_str(&format!("\nRunoff, {} vs {}:\n", vals[ro.a], vals[ro.b]));
+                    result.push_str(&format!("{}: {}\n", ro.a_pref, vals[ro.a]));
+                    result.push_str(&format!("{}: {}\n", ro.b_pref, vals[ro.b]));
+                    result.push_str(&format!("{}: No Preference\n", ballots.len() as u64 - ro.a_pref - ro.b_pref));
+                    result.push_str(&format!("Runoff Winner: {}\n", vals[ro.winner]));
+                }
+                (winners, self.submittedvotes.len(), result)
+            },
+            VOTE_MJ => {
+                let ballots: Vec<Vec<(usize, usize)>> = self.weighted_ballots(|cv| cv.get_grade_vec());
+                let grades = majority_judgment(&ballots, vals.len());
+                let winners = mj_winners(&grades, num_winners);
+
+                let mut result: String = String::from("\nMedian Grades:\n");
+                for (c, g) in &grades {
+                    let median = if g.is_empty() { 0 } else { g[(g.len() - 1) / 2] };
+                    let dist: Vec<String> = (0..MJ_GRADES.len()).rev().map(|i| {
+                        format!("{} {}", g.iter().filter(|x| **x == i).count(), MJ_GRADES[i])
+                    }).collect();
+                    result.push_str(&format!("{}: {} ({})\n", MJ_GRADES[median], vals[*c], dist.join(", ")));
+                }
+                (winners, self.submittedvotes.len(), result)
             },
             _ => panic!("Tried to get results with unknown vote type"),
         }
@@ -416,29 +2171,62 @@ fn create_user_message<'a, 'b, 'c>(mut c: &'b mut CreateComponents, vals: &'c Ve
                     let s = if let Some(score) = m.get(&vali) {
                         *score
                     } else {
-                        0.0
+                        vote.score_settings.default_score()
                     };
-                    format!(": {}", s)
+                    if vote.kind == VOTE_QUADRATIC {
+                        format!(": {} ({} credits)", s, s * s)
+                    } else {
+                        format!(": {}", s)
+                    }
                 },
                 CastVotes::Rank(m) => {
-                    let s = if let Some(score) = m.get(&vali) {
-                        *score
+                    if let Some(rank) = m.get(&vali) {
+                        format!(": Rank {}", rank)
                     } else {
-                        vals.len()
-                    };
-                    format!(": Rank {}", s)
-                }
+                        String::from(": Unranked")
+                    }
+                },
+                CastVotes::Grade(m) => {
+                    format!(": {}", MJ_GRADES[*m.get(&vali).unwrap_or(&0)])
+                },
+                CastVotes::Points(m) => {
+                    format!(": {}", m.get(&vali).unwrap_or(&0))
+                },
             }
         } else {
             String::from("")
         };
 
-        c = c.create_action_row(|r| {
-            r.create_button(|btn| {
+        // points ballots move points with -/+ so they can't go over the budget
+        let points = match vote.uservotes.get(&uid).map(|uv| &uv.votes) {
+            Some(CastVotes::Points(m)) => Some((*m.get(&vali).unwrap_or(&0), vote.points_left(m))),
+            _ => None,
+        };
+
+        c = c.create_action_row(|mut r| {
+            if let Some((p, _)) = points {
+                r = r.create_button(|btn| {
+                    btn.custom_id(format!("{}{}", ID_VOTE_MINUS_PREFIX, vali))
+                        .style(ButtonStyle::Secondary)
+                        .label("-")
+                        .disabled(p == 0)
+                });
+            }
+            r = r.create_button(|btn| {
                 btn.custom_id(format!("{}{}", ID_VOTE_VAL_PREFIX, vali))
                     .style(ButtonStyle::Secondary)
                     .label(format!("{}{}", vals[vali], item_note))
-            })
+                    .disabled(points.is_some())
+            });
+            if let Some((_, left)) = points {
+                r = r.create_button(|btn| {
+                    btn.custom_id(format!("{}{}", ID_VOTE_PLUS_PREFIX, vali))
+                        .style(ButtonStyle::Secondary)
+                        .label("+")
+                        .disabled(left == 0)
+                });
+            }
+            r
         });
     }
     // add a row for the movement and submit buttons
@@ -457,11 +2245,26 @@ fn create_user_message<'a, 'b, 'c>(mut c: &'b mut CreateComponents, vals: &'c Ve
                         .label(">")
                 });
         }
-        r.create_button(|btn| {
+        r = r.create_button(|btn| {
             btn.custom_id(ID_VOTE_SUBMIT)
                 .style(ButtonStyle::Primary)
                 .label("Submit")
         })
+        .create_button(|btn| {
+            btn.custom_id(ID_VOTE_ABSTAIN)
+                .style(ButtonStyle::Secondary)
+                .label("Abstain")
+        });
+
+        if vote.write_ins {
+            r = r.create_button(|btn| {
+                btn.custom_id(ID_VOTE_WRITEIN)
+                    .style(ButtonStyle::Secondary)
+                    .label("Write-in")
+            });
+        }
+
+        r
     })
 }
 
@@ -477,15 +2280,15 @@ macro_rules! user_vote_message {
 
         let mut can_vote = true;
 
-        let disppage = {
+        let (disppage, status) = {
             let rvote = $vote.read().unwrap();
 
             if $vote_once {
-                can_vote = !rvote.submittedvotes.contains_key(&$uid);
+                can_vote = !rvote.submittedvotes.contains_key(&$uid) && !rvote.abstains.contains(&$uid);
             }
 
             if let Some(uv) = rvote.uservotes.get(&$uid) {
-                uv.page
+                (uv.page, rvote.ballot_status(&uv.votes))
             } else {
                 panic!("Tried to get user page for user not in vote structure yet");
             }
@@ -495,7 +2298,7 @@ macro_rules! user_vote_message {
             $interaction.create_interaction_response($ctx, |resp| {
                 resp.kind(irkind).interaction_response_data(|d| {
                     d
-                        .content(format!("Page {}/{}{}", disppage+1, $num_pages, $extra))
+                        .content(format!("Page {}/{}{}{}", disppage+1, $num_pages, status, $extra))
                         .components(|c| {
                             let rvote = $vote.read().unwrap();
                             create_user_message(c, &$vals, disppage, &rvote, $uid)
@@ -525,22 +2328,32 @@ macro_rules! user_vote_message {
     };
 }
 
-async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
+async fn start_vote(ctx: &Context, cid: ChannelId, mut vi: VoteInfo) {
+    if vi.none_option && !vi.vals.iter().any(|v| v == NONE_CHOICE) {
+        vi.vals.push(String::from(NONE_CHOICE));
+    }
+
+    // suggestions, vetoes and runoffs change the number of choices, so the minimum has to still be possible
+    vi.select_limits.min = vi.select_limits.min.min(vi.vals.len());
+
     let pingstr = vi.get_ping();
     let timestr = vi.get_timeout_str(" hr ");
-    let VoteInfo{kind: votetype, mut vals, timeout, show_at_timeout, vote_once, .. } = vi;
+    let kindstr = vi.get_kind_str();
+    let vote = Arc::new(RwLock::new(Vote::new(&vi)));
+    let mut runoff_vi = vi.clone();
+    runoff_vi.vetoes.clear();
+    let VoteInfo{kind: votetype, creator, mut vals, timeout, show_at_timeout, vote_once, score_settings, select_limits, tie_break, .. } = vi;
 
-    let vote = Arc::new(RwLock::new(Vote::new(votetype)));
-    let num_pages = ((vals.len() -1) / PERPAGE) + 1;
+    let mut num_pages = ((vals.len() -1) / PERPAGE) + 1;
 
     // actually let's try just having a "vote" button, so we can edit the ephemeral button to match each user
     let basemsg = cid.send_message(ctx, |m| {
-        setup_base_message!(vi.prompt, timestr, m, 0, votetype.to_string(), pingstr)
+        setup_base_message!(vi.prompt, timestr, m, 0, kindstr, pingstr)
     }).await.unwrap();
 
     // first let's keep each game name under 33 char
     for v in &mut vals {
-        v.truncate(33)
+        *v = shorten_choice(v);
     }
 
     // send a ephemeral message (or multiple) to the channel for everyone, with the voting options
@@ -678,7 +2491,7 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
                         if vote_once {
                             {
                                 let rvote = vote.read().unwrap();
-                                if !rvote.submittedvotes.contains_key(&uid) {
+                                if !rvote.submittedvotes.contains_key(&uid) && !rvote.abstains.contains(&uid) {
                                     isfirst = true;
                                 }
                             };
@@ -692,19 +2505,24 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
                         if dosubmit {
                             // submit the vote for this user, if we can
                             // first check that it is a valid submission, and let them know if it is not
-                            let valid_submission;
+                            let valid_submission: Result<(), String>;
 
                             let mut subcount = 0;
                             {
                                 let mut wvote = vote.write().unwrap();
 
-                                if let Some(uv) = wvote.uservotes.get_mut(&uid) {
-                                    valid_submission = uv.votes.are_valid(votetype, vals.len());
+                                let weight = wvote.resolve_weight(interaction.member.as_ref());
+                                wvote.weights.insert(uid, weight);
 
-                                    if valid_submission {
-                                        let ballot = uv.votes.get_ballot(vals.len());
-                                        if wvote.submittedvotes.insert(uid, ballot).is_none() {
-                                            subcount = wvote.submittedvotes.len();
+                                if let Some(uv) = wvote.uservotes.get_mut(&uid) {
+                                    // check the ballot as it will be counted, with the defaults filled in
+                                    let ballot = uv.votes.get_ballot(vals.len(), &score_settings);
+                                    valid_submission = ballot.are_valid(votetype, vals.len(), &score_settings, &select_limits);
+
+                                    if valid_submission.is_ok() {
+                                        let abstained = wvote.abstains.remove(&uid);
+                                        if wvote.submittedvotes.insert(uid, ballot).is_none() && !abstained {
+                                            subcount = wvote.submittedvotes.len() + wvote.abstains.len();
                                             isfirst = true;
                                         }
                                     }
@@ -713,15 +2531,15 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
                                 }
                             }
 
-                            if !valid_submission {
+                            if let Err(e) = valid_submission {
                                 // return an error to the user
-                                let errresp = format!("\nError: invalid values for a {} vote, please fix your vote.\nEach should be a {}", votetype.to_string(), votetype.value_name());
+                                let errresp = format!("\nError: {}", e);
                                 user_vote_message!(interaction, uid, errresp, vote, ctx, num_pages, vals, false, vote_once);
                             } else {
                                 // update the count
                                 if isfirst {
                                     cid.edit_message(ctx, basemsg.id, |e| {
-                                        setup_base_message!(vi.prompt, timestr, e, subcount, votetype.to_string(), pingstr)
+                                        setup_base_message!(vi.prompt, timestr, e, subcount, kindstr, pingstr)
                                     }).await.unwrap();
                                 }
                                 showresults = true;
@@ -745,12 +2563,93 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
                             interaction.create_interaction_response(ctx, |resp| {
                                 resp.kind(InteractionResponseType::ChannelMessageWithSource).interaction_response_data(|d| {
                                     d
-                                        .content(resultsmsg)
+                                        .content(clip_lines(&resultsmsg, MAX_MSG_LEN))
                                         .ephemeral(true)
                                 })
                             }).await.unwrap();
                         }
                     },
+                    ID_VOTE_WRITEIN => {
+                        // pop a modal for the new choice
+                        interaction.create_interaction_response(ctx, |resp| {
+                            resp.kind(InteractionResponseType::Modal).interaction_response_data(|d| {
+                                d.custom_id(ID_VOTE_WRITEIN_INPUT)
+                                    .title("Write-in a Choice")
+                                    .components(|c| {
+                                        c.create_action_row(|r| {
+                                            r.create_input_text(|t| {
+                                                t.custom_id(ID_VOTE_WRITEIN_INPUT_TXT)
+                                                    .style(InputTextStyle::Short)
+                                                    .label("Choice")
+                                                    .min_length(1)
+                                                    .max_length(MAX_CHOICE_LEN as u64)
+                                                    .required(true)
+                                            })
+                                        })
+                                    })
+                            })
+                        }).await.unwrap();
+                    },
+                    ID_VOTE_ABSTAIN => {
+                        // counts as taking part, but takes back any submitted ballot
+                        let mut subcount = 0;
+                        let mut already_voted = false;
+                        {
+                            let mut wvote = vote.write().unwrap();
+
+                            if vote_once && wvote.submittedvotes.contains_key(&uid) {
+                                already_voted = true;
+                            } else {
+                                let submitted = wvote.submittedvotes.remove(&uid).is_some();
+                                if wvote.abstains.insert(uid) && !submitted {
+                                    subcount = wvote.submittedvotes.len() + wvote.abstains.len();
+                                }
+                            }
+                        }
+
+                        if subcount > 0 {
+                            cid.edit_message(ctx, basemsg.id, |e| {
+                                setup_base_message!(vi.prompt, timestr, e, subcount, kindstr, pingstr)
+                            }).await.unwrap();
+                        }
+
+                        let extra = if already_voted {
+                            "\nAlready voted"
+                        } else if vote_once {
+                            "\nAbstained"
+                        } else {
+                            "\nAbstained, submit to vote instead"
+                        };
+                        user_vote_message!(interaction, uid, extra, vote, ctx, num_pages, vals, false, vote_once);
+                    },
+                    pm if pm.starts_with(ID_VOTE_PLUS_PREFIX) || pm.starts_with(ID_VOTE_MINUS_PREFIX) => {
+                        let plus = pm.starts_with(ID_VOTE_PLUS_PREFIX);
+                        let prefix_len = if plus { ID_VOTE_PLUS_PREFIX.len() } else { ID_VOTE_MINUS_PREFIX.len() };
+                        let num = pm[prefix_len..].parse::<usize>().unwrap();
+
+                        {
+                            let mut wvote = vote.write().unwrap();
+                            let budget = wvote.score_settings.budget as usize;
+
+                            if let Some(uv) = wvote.uservotes.get_mut(&uid) {
+                                if let CastVotes::Points(m) = &mut uv.votes {
+                                    let used: usize = m.values().sum();
+                                    let p = m.entry(num).or_insert(0);
+                                    if plus && used < budget {
+                                        *p += 1;
+                                    } else if !plus && *p > 0 {
+                                        *p -= 1;
+                                    }
+                                } else {
+                                    panic!("Got a points interaction for a ballot without points");
+                                }
+                            } else {
+                                panic!("Somehow got a points interaction without an entry in the vote map?")
+                            }
+                        }
+
+                        user_vote_message!(interaction, uid, "", vote, ctx, num_pages, vals, false, vote_once);
+                    },
                     value_id => {
                         // find which value the vote is for
                         if !value_id.starts_with(ID_VOTE_VAL_PREFIX) {
@@ -758,7 +2657,7 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
                         }
 
                         let num = value_id[ID_VOTE_VAL_PREFIX.len()..].parse::<usize>().unwrap();
-                        let mut current_score_f: f32 = 0.0;
+                        let mut current_score_f: f32 = score_settings.default_score();
                         let mut refresh_msg = true;
 
                         println!("Vote for value {} ({})", num, vals[num]);
@@ -774,6 +2673,10 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
                                         if v.contains(&num) {
                                             v.retain(|&x| x != num);
                                         } else {
+                                            if votetype == VOTE_PLURALITY || votetype == VOTE_RBALLOT {
+                                                // only one choice, replace the previous one
+                                                v.clear();
+                                            }
                                             v.push(num);
                                         }
                                     },
@@ -787,20 +2690,32 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
                                         refresh_msg = false;
                                     },
                                     CastVotes::Rank(m) => {
-                                        // rank++
+                                        // rank++, going back to unranked after last place
+                                        // more than one choice can have the same rank
                                         let rank = match m.get(&num) {
-                                            Some(score) => *score,
-                                            _ => vals.len(),
+                                            Some(score) => *score + 1,
+                                            _ => 1,
                                         };
 
-                                        let mut rank = rank + 1;
                                         if rank > vals.len() {
-                                            rank = 1;
+                                            m.remove(&num);
+                                        } else {
+                                            m.insert(num, rank);
                                         }
-                                        
-                                        m.insert(num, rank);
                                         // no modal, just refresh the message
                                     },
+                                    CastVotes::Grade(m) => {
+                                        // next grade, wrapping back around to the lowest
+                                        let grade = match m.get(&num) {
+                                            Some(g) => (*g + 1) % MJ_GRADES.len(),
+                                            _ => 1,
+                                        };
+
+                                        m.insert(num, grade);
+                                    },
+                                    CastVotes::Points(_m) => {
+                                        // the choice button is disabled, points go through -/+
+                                    },
                                 }
                             } else {
                                 panic!("Somehow got a vote interaction without an entry in the vote map?")
@@ -819,10 +2734,10 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
                                                 r.create_input_text(|t| {
                                                     t.custom_id(format!("{}{}", ID_VOTE_VAL_INPUT_PREFIX, num))
                                                         .style(InputTextStyle::Short)
-                                                        .label(votetype.value_name())
+                                                        .label(votetype.value_name(&score_settings))
                                                         .value(current_score_f.to_string())
                                                         .min_length(1)
-                                                        .max_length(5)
+                                                        .max_length(8)
                                                         .required(true)
                                                 })
                                             })
@@ -837,7 +2752,35 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
             },
             Some(interaction) = mod_col.next() => {
                 println!("Got Modal Vote Interaction");
-                if let ActionRowComponent::InputText(it) = &interaction.data.components[0].components[0] {
+                if interaction.data.custom_id == ID_VOTE_WRITEIN_INPUT {
+                    let uid = interaction.user.id;
+
+                    let errresp = if let ActionRowComponent::InputText(it) = &interaction.data.components[0].components[0] {
+                        let v = shorten_choice(content_safe(ctx, it.value.trim(), &ContentSafeOptions::default(), &[]).trim());
+
+                        if v.is_empty() {
+                            String::from("\nError: the write-in is empty")
+                        } else if vals.iter().any(|x| x.eq_ignore_ascii_case(&v)) {
+                            format!("\nError: {} is already a choice", v)
+                        } else {
+                            println!("Write-in {}", v);
+                            vals.push(v.clone());
+                            num_pages = ((vals.len() -1) / PERPAGE) + 1;
+
+                            // ballots already in get the default for the new choice
+                            let mut wvote = vote.write().unwrap();
+                            for cv in wvote.submittedvotes.values_mut() {
+                                *cv = cv.get_ballot(vals.len(), &score_settings);
+                            }
+
+                            format!("\nAdded {}", v)
+                        }
+                    } else {
+                        panic!("Write-in modal didn't have input text?");
+                    };
+
+                    user_vote_message!(interaction, uid, errresp, vote, ctx, num_pages, vals, false, vote_once);
+                } else if let ActionRowComponent::InputText(it) = &interaction.data.components[0].components[0] {
                     let uid = interaction.user.id;
 
                     // parse custom id to get vote index
@@ -851,21 +2794,21 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
                     // parse value to get f32 value
                     let score: f32 = match it.value.parse::<f32>() {
                         Ok(s) => {
-                            badvalue = votetype.is_bad_value(s, &vals);
+                            badvalue = votetype.is_bad_value(s, &vals, &score_settings);
                             if badvalue {
-                                0.0
+                                score_settings.default_score()
                             } else {
                                 s
                             }
                         },
                         Err(_) => {
                             badvalue = true;
-                            0.0
+                            score_settings.default_score()
                         },
                     };
 
                     let errresp = if badvalue {
-                        println!("Changing bad value {:?} to 0.0", it.value);
+                        println!("Changing bad value {:?} to {}", it.value, score_settings.default_score());
                         format!("\nError: Bad Value")
                     } else {
                         String::from("")
@@ -885,6 +2828,12 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
                                 CastVotes::Rank(_m) => {
                                     panic!("Got modal response for a rank vote?");
                                 },
+                                CastVotes::Grade(_m) => {
+                                    panic!("Got modal response for a grade vote?");
+                                },
+                                CastVotes::Points(_m) => {
+                                    panic!("Got modal response for a points vote?");
+                                },
                             }
                         } else {
                             panic!("Somehow got a modal interaction without an entry in the vote map?")
@@ -905,18 +2854,219 @@ async fn start_vote(ctx: &Context, cid: ChannelId, vi: VoteInfo) {
         };
     } // end select loop
 
+    // voting is over, so the seed for any draws can be picked now
+    vote.write().unwrap().close();
+
+    // a vote that missed the quorum or threshold has no winner to break ties for
+    let no_decision = {
+        let rvote = vote.read().unwrap();
+        rvote.check_decision(&vals)
+    };
+
+    // if no game was picked, the creator can take suggestions again
+    let can_reopen = no_decision.is_none() && show_at_timeout && {
+        let rvote = vote.read().unwrap();
+        rvote.none_won(&vals)
+    };
+
+    // a tie with the runoff tie break goes straight to a new vote
+    let auto_runoff = no_decision.is_none() && !can_reopen && tie_break == TieBreak::Runoff && votetype.has_runoff() && {
+        let rvote = vote.read().unwrap();
+        rvote.get_tie(&vals).is_some()
+    };
+
+    // if the creator breaks ties, they pick from the tied choices on the final message
+    let mut tie = if no_decision.is_none() && !can_reopen && show_at_timeout && tie_break == TieBreak::Creator {
+        let rvote = vote.read().unwrap();
+        rvote.get_tie(&vals)
+    } else {
+        None
+    };
+
+    // the creator can also start a runoff from the final results
+    let can_runoff = votetype.has_runoff();
+    let final_btns = show_at_timeout && !auto_runoff && (can_runoff || can_reopen || tie.is_some());
+
     // update the main message to indicate the vote is over, could display final results too, depending on settings
+    let finalmsg = if let Some(reason) = &no_decision {
+        if show_at_timeout {
+            let rvote = vote.read().unwrap();
+            format!("No Decision\n{}\n\n{}", reason, rvote.get_results(&vals, "\nThanks!"))
+        } else {
+            format!("Vote Finished\nNo Decision\n{}", reason)
+        }
+    } else if show_at_timeout {
+        let rvote = vote.read().unwrap();
+        let mut resultsmsg = rvote.get_results(&vals, "\nThanks!");
+        if tie.is_some() {
+            resultsmsg.push_str(&format!("\n{} pick from the tied choices:", creator.mention()));
+        }
+        resultsmsg
+    } else {
+        format!("Vote Finished")
+    };
     cid.edit_message(ctx, basemsg.id, |e| {
-        e.content(
-            if show_at_timeout {
-                let rvote = vote.read().unwrap();
-                rvote.get_results(&vals, "\nThanks!")
+        e.content(clip_lines(&finalmsg, MAX_MSG_LEN)).components(|c| {
+            if final_btns {
+                create_final_comp(c, &vals, &tie, can_runoff, can_reopen)
             } else {
-                format!("Vote Finished")
+                c
             }
-        ).components(|c| c)
+        })
     }).await.unwrap();
 
+    let mut do_runoff = auto_runoff;
+    let mut reopen_by: Option<User> = None;
+
+    if final_btns {
+        let mut final_col = basemsg.await_component_interactions(ctx)
+            .timeout(VOTE_DM_TIMEOUT)
+            .build();
+
+        while let Some(interaction) = final_col.next().await {
+            if interaction.user.id != creator {
+                interaction.create_interaction_response(ctx, |resp| {
+                    resp.kind(InteractionResponseType::ChannelMessageWithSource).interaction_response_data(|d| {
+                        d
+                            .content(format!("Sorry, only {} can do that", creator.mention()))
+                            .ephemeral(true)
+                    })
+                }).await.unwrap();
+                continue;
+            }
+
+            match &interaction.data.custom_id[..] {
+                ID_VOTE_RUNOFF => {
+                    interaction.create_interaction_response(ctx, |resp| {
+                        resp.kind(InteractionResponseType::UpdateMessage).interaction_response_data(|d| {
+                            d.components(|c| c)
+                        })
+                    }).await.unwrap();
+
+                    do_runoff = true;
+                    break;
+                },
+                ID_VOTE_REOPEN => {
+                    interaction.create_interaction_response(ctx, |resp| {
+                        resp.kind(InteractionResponseType::UpdateMessage).interaction_response_data(|d| {
+                            d.components(|c| c)
+                        })
+                    }).await.unwrap();
+
+                    reopen_by = Some(interaction.user.clone());
+                    break;
+                },
+                tie_id => {
+                    if !tie_id.starts_with(ID_VOTE_TIE_PREFIX) {
+                        panic!("Unknown component value from final vote message {}", tie_id);
+                    }
+                    let num = tie_id[ID_VOTE_TIE_PREFIX.len()..].parse::<usize>().unwrap();
+
+                    let mut resultsmsg;
+                    {
+                        let mut wvote = vote.write().unwrap();
+                        wvote.tie_picks.push(num);
+                        tie = wvote.get_tie(&vals);
+                        resultsmsg = wvote.get_results(&vals, "\nThanks!");
+                    }
+                    if tie.is_some() {
+                        // more than one winning spot was tied, keep picking
+                        resultsmsg.push_str(&format!("\n{} pick from the tied choices:", creator.mention()));
+                    }
+
+                    interaction.create_interaction_response(ctx, |resp| {
+                        resp.kind(InteractionResponseType::UpdateMessage).interaction_response_data(|d| {
+                            d
+                                .content(clip_lines(&resultsmsg, MAX_MSG_LEN))
+                                .components(|c| create_final_comp(c, &vals, &tie, can_runoff, can_reopen))
+                        })
+                    }).await.unwrap();
+                },
+            }
+        }
+
+        if !do_runoff && reopen_by.is_none() {
+            println!("Ending collection for final vote message! Timed out");
+            cid.edit_message(ctx, basemsg.id, |e| {
+                e.components(|c| c)
+            }).await.unwrap();
+        }
+    }
+
+    if do_runoff {
+        // a runoff is only between real choices
+        let choices: Vec<usize> = {
+            let rvote = vote.read().unwrap();
+            rvote.get_runoff_choices(&vals).into_iter().filter(|c| Some(*c) != rvote.none_choice).collect()
+        };
+
+        if choices.len() < 2 {
+            println!("Not enough leading choices for a runoff");
+            return;
+        }
+
+        // same settings, just the leading choices
+        runoff_vi.none_option = false;
+        runoff_vi.vals = choices.iter().map(|c| vals[*c].clone()).collect();
+        runoff_vi.prompt = if runoff_vi.prompt.is_empty() {
+            String::from("Runoff\n")
+        } else {
+            format!("Runoff: {}", runoff_vi.prompt)
+        };
+
+        Box::pin(start_vote(ctx, cid, runoff_vi)).await;
+    } else if let Some(author) = reopen_by {
+        // back to suggestions with the same choices and any write-ins, "None of these" gets added again when the vote starts
+        runoff_vi.vals = vals.iter().filter(|v| *v != NONE_CHOICE).cloned().collect();
+        Box::pin(handle_suggestion_phase(ctx, &author, cid, runoff_vi)).await;
+    }
+}
+
+fn create_final_comp<'a>(mut c: &'a mut CreateComponents, vals: &[String], tie: &Option<Vec<usize>>, runoff: bool, reopen: bool) -> &'a mut CreateComponents {
+    // a button for each tied choice, five to a row
+    if let Some(tied) = tie {
+        for row in tied.chunks(5).take(MAX_TIE_BTNS / 5) {
+            c = c.create_action_row(|mut r| {
+                for t in row {
+                    r = r.create_button(|b| {
+                        b
+                            .custom_id(format!("{}{}", ID_VOTE_TIE_PREFIX, t))
+                            .style(ButtonStyle::Secondary)
+                            .label(&vals[*t])
+                    });
+                }
+                r
+            });
+        }
+    }
+
+    if !runoff && !reopen {
+        return c;
+    }
+
+    // start a new vote with just the leaders
+    c.create_action_row(|mut r| {
+        if runoff {
+            r = r.create_button(|b| {
+                b
+                    .custom_id(ID_VOTE_RUNOFF)
+                    .style(ButtonStyle::Primary)
+                    .label("Runoff")
+            });
+        }
+
+        // or go back to suggestions when nothing was picked
+        if reopen {
+            r = r.create_button(|b| {
+                b
+                    .custom_id(ID_VOTE_REOPEN)
+                    .style(ButtonStyle::Secondary)
+                    .label("Reopen Suggestions")
+            });
+        }
+
+        r
+    })
 }
 
 fn create_sug_comp<'a, 'b>(mut c: &'a mut CreateComponents) -> &'a mut CreateComponents {
@@ -1128,8 +3278,245 @@ async fn handle_suggestion_phase(ctx: &Context, author: &User, cid: ChannelId, m
     drop(mod_col);
 
     if do_vote {
-        start_vote(ctx, cid, vi).await;
+        if vi.veto_phase {
+            handle_veto_phase(ctx, author, cid, vi).await;
+        } else {
+            start_vote(ctx, cid, vi).await;
+        }
+    }
+}
+
+fn create_veto_comp(c: &mut CreateComponents) -> &mut CreateComponents {
+    c.create_action_row(|r| {
+        r.create_button(|b| {
+            b
+                .custom_id(ID_VETO_BTN)
+                .style(ButtonStyle::Danger)
+                .label("Veto a Choice")
+        })
+        .create_button(|b| {
+            b
+                .custom_id(ID_VETO_SUB_BTN)
+                .style(ButtonStyle::Secondary)
+                .label("Start Vote")
+        })
+    })
+}
+
+fn create_veto_user_comp<'a>(mut c: &'a mut CreateComponents, vals: &[String], pick: Option<usize>, page: usize) -> &'a mut CreateComponents {
+    // a button for each choice on this page, five to a row
+    let start = page * VETO_PERPAGE;
+    let end = (start + VETO_PERPAGE).min(vals.len());
+    for (rowi, row) in vals[start..end].chunks(5).enumerate() {
+        c = c.create_action_row(|mut r| {
+            for (j, v) in row.iter().enumerate() {
+                let vali = start + (rowi * 5) + j;
+                r = r.create_button(|b| {
+                    b
+                        .custom_id(format!("{}{}", ID_VETO_VAL_PREFIX, vali))
+                        .style(if pick == Some(vali) { ButtonStyle::Danger } else { ButtonStyle::Secondary })
+                        // choices aren't shortened until the vote starts, and labels can't be long
+                        .label(shorten_choice(v))
+                });
+            }
+            r
+        });
+    }
+
+    if vals.len() > VETO_PERPAGE {
+        c = c.create_action_row(|r| {
+            r
+                .create_button(|btn| {
+                    btn.custom_id(ID_VETO_LEFT)
+                        .style(ButtonStyle::Secondary)
+                        .label("<")
+                })
+                .create_button(|btn| {
+                    btn.custom_id(ID_VETO_RIGHT)
+                        .style(ButtonStyle::Secondary)
+                        .label(">")
+                })
+        });
+    }
+    c
+}
+
+fn get_veto_user_content(vals: &[String], pick: Option<usize>, page: usize) -> String {
+    let num_pages = ((vals.len().max(1) - 1) / VETO_PERPAGE) + 1;
+    let pagestr = if num_pages > 1 { format!(" (page {}/{})", page + 1, num_pages) } else { String::new() };
+    match pick {
+        Some(p) => format!("You vetoed {}{}", shorten_choice(&vals[p]), pagestr),
+        None => format!("Pick a choice to veto{}", pagestr),
+    }
+}
+
+fn get_veto_content(vi: &VoteInfo, num_vetoes: usize) -> String {
+    format!("{}{}Veto round for the vote:\n{}\nEveryone can strike one choice they won't play\n{} vetoes so far\n", vi.get_ping(), vi.get_timeout_str(" hr "), vi.prompt, num_vetoes)
+}
+
+async fn handle_veto_phase(ctx: &Context, author: &User, cid: ChannelId, mut vi: VoteInfo) {
+    let mut msg = cid.send_message(ctx, |m| {
+        m
+            .content(get_veto_content(&vi, 0))
+            .components(create_veto_comp)
+    }).await.unwrap();
+
+    // each user's veto, the page they are on, and the ephemeral message they pick it on
+    let mut vetoes: HashMap<UserId, usize> = HashMap::new();
+    let mut pages: HashMap<UserId, usize> = HashMap::new();
+    let num_pages = ((vi.vals.len().max(1) - 1) / VETO_PERPAGE) + 1;
+    let veto_msgs: Arc<RwLock<HashMap<UserId, MessageId>>> = Arc::new(RwLock::new(HashMap::new()));
+
+    let mut m_col = msg.await_component_interactions(ctx)
+        .timeout(vi.timeout)
+        .build();
+
+    let filter_msgs = veto_msgs.clone();
+    let mut pick_col = ComponentInteractionCollectorBuilder::new(ctx)
+        .timeout(vi.timeout)
+        .channel_id(cid)
+        .filter(move |i| {
+            let rmsgs = filter_msgs.read().unwrap();
+            rmsgs.get(&i.user.id) == Some(&i.message.id)
+        })
+        .build();
+
+    loop {
+        tokio::select! {
+            Some(interaction) = m_col.next() => {
+                let uid = interaction.user.id;
+
+                match &interaction.data.custom_id[..] {
+                    ID_VETO_BTN => {
+                        let pick = vetoes.get(&uid).copied();
+                        pages.insert(uid, 0);
+                        interaction.create_interaction_response(ctx, |resp| {
+                            resp.kind(InteractionResponseType::ChannelMessageWithSource).interaction_response_data(|d| {
+                                d
+                                    .content(get_veto_user_content(&vi.vals, pick, 0))
+                                    .components(|c| create_veto_user_comp(c, &vi.vals, pick, 0))
+                                    .ephemeral(true)
+                            })
+                        }).await.unwrap();
+
+                        let respmsg = interaction.get_interaction_response(ctx).await.unwrap();
+                        veto_msgs.write().unwrap().insert(uid, respmsg.id);
+                    },
+                    ID_VETO_SUB_BTN => {
+                        // only the author can hit this
+                        if uid != author.id {
+                            interaction.create_interaction_response(ctx, |resp| {
+                                resp.kind(InteractionResponseType::ChannelMessageWithSource).interaction_response_data(|d| {
+                                    d
+                                        .content(format!("Sorry, only {} can start the vote", author.name))
+                                        .ephemeral(true)
+                                })
+                            }).await.unwrap();
+                        } else {
+                            interaction.create_interaction_response(ctx, |resp| {
+                                resp.kind(InteractionResponseType::UpdateMessage).interaction_response_data(|d| {
+                                    d
+                                        .content("Starting Vote...")
+                                        .components(|c| c)
+                                })
+                            }).await.unwrap();
+                            break;
+                        }
+                    },
+                    _ => {
+                        panic!("Got unexpected button id on the veto msg");
+                    },
+                }
+            },
+            Some(interaction) = pick_col.next() => {
+                let uid = interaction.user.id;
+                let veto_id = &interaction.data.custom_id[..];
+                let mut page = *pages.get(&uid).unwrap_or(&0);
+
+                let pick = match veto_id {
+                    ID_VETO_LEFT => {
+                        page = if page == 0 { num_pages - 1 } else { page - 1 };
+                        vetoes.get(&uid).copied()
+                    },
+                    ID_VETO_RIGHT => {
+                        page = if page + 1 >= num_pages { 0 } else { page + 1 };
+                        vetoes.get(&uid).copied()
+                    },
+                    _ => {
+                        if !veto_id.starts_with(ID_VETO_VAL_PREFIX) {
+                            panic!("Unknown component value from veto message {}", veto_id);
+                        }
+                        let num = veto_id[ID_VETO_VAL_PREFIX.len()..].parse::<usize>().unwrap();
+
+                        // only one veto each, picking it again takes it back
+                        if vetoes.get(&uid) == Some(&num) {
+                            vetoes.remove(&uid);
+                            None
+                        } else {
+                            vetoes.insert(uid, num);
+                            Some(num)
+                        }
+                    },
+                };
+                pages.insert(uid, page);
+
+                interaction.create_interaction_response(ctx, |resp| {
+                    resp.kind(InteractionResponseType::UpdateMessage).interaction_response_data(|d| {
+                        d
+                            .content(get_veto_user_content(&vi.vals, pick, page))
+                            .components(|c| create_veto_user_comp(c, &vi.vals, pick, page))
+                    })
+                }).await.unwrap();
+
+                msg.edit(ctx, |e| {
+                    e.content(get_veto_content(&vi, vetoes.len()))
+                }).await.unwrap();
+            },
+            else => {
+                println!("Ending collection for veto msg! Timed out");
+                msg.edit(ctx, |e| {
+                    e.content("Time Up! Starting Vote...").components(|c| c)
+                }).await.unwrap();
+                break;
+            }
+        }
+    } // end select loop
+
+    drop(m_col);
+    drop(pick_col);
+
+    // strike every vetoed choice
+    let mut counts: Vec<usize> = vec![0; vi.vals.len()];
+    for v in vetoes.values() {
+        counts[*v] += 1;
+    }
+
+    let mut struck: Vec<(String, usize)> = Vec::new();
+    let mut survivors: Vec<String> = Vec::new();
+    for (v, n) in vi.vals.iter().zip(counts) {
+        if n > 0 {
+            struck.push((shorten_choice(v), n));
+        } else {
+            survivors.push(v.clone());
+        }
+    }
+    struck.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+
+    if survivors.len() < 2 {
+        let mut endmsg = String::from("Not enough choices survived the vetoes to vote\nVetoed:\n");
+        for (v, n) in &struck {
+            endmsg.push_str(&format!("{} ({})\n", v, n));
+        }
+        for v in &survivors {
+            endmsg.push_str(&format!("Left: {}\n", v));
+        }
+        cid.send_message(ctx, |m| m.content(endmsg)).await.unwrap();
+        return;
     }
+
+    vi.vals = survivors;
+    vi.vetoes = struck;
+    start_vote(ctx, cid, vi).await;
 }
 
 fn create_dm_vote_comp<'a, 'b>(mut c: &'a mut CreateComponents, vi: &'b VoteInfo) -> &'a mut CreateComponents {
@@ -1230,35 +3617,171 @@ fn create_dm_vote_comp<'a, 'b>(mut c: &'a mut CreateComponents, vi: &'b VoteInfo
                 ))
         });
 
-        // timeout
+        // timeout
+        r = r.create_button(|b| {
+            b
+                .custom_id(ID_BUILD_DUR_BTN)
+                .style(ButtonStyle::Secondary)
+                .label(format!("Vote Timeout = {}",
+                    vi.get_timeout_str(" hr")
+                ))
+        });
+
+        // number of winners
+        r = r.create_button(|b| {
+            b
+                .custom_id(ID_BUILD_WINNERS_BTN)
+                .style(ButtonStyle::Secondary)
+                .label(format!("Winners = {}", vi.num_winners))
+        });
+
+        // borda points
+        if vi.kind == VOTE_BORDA {
+            r = r.create_button(|b| {
+                b
+                    .custom_id(ID_BUILD_BORDA_BTN)
+                    .style(ButtonStyle::Secondary)
+                    .label(format!("Borda Points = {}", vi.borda_variant))
+            });
+        }
+
+        // tie break
+        r = r.create_button(|b| {
+            b
+                .custom_id(ID_BUILD_TIE_BTN)
+                .style(ButtonStyle::Secondary)
+                .label(format!("Tie Break = {}", vi.tie_break))
+        });
+
+        // how many choices an approval ballot can have
+        if vi.kind.is_approval_ballot() {
+            r = r.create_button(|b| {
+                b
+                    .custom_id(ID_BUILD_SELECT_BTN)
+                    .style(ButtonStyle::Secondary)
+                    .label(format!("Selections = {}", vi.select_limits.range_str()))
+            });
+        }
+
+        // score range and budget
+        if vi.kind.is_score_ballot() || matches!(vi.kind, VOTE_LSCORE | VOTE_CUMULATIVE | VOTE_QUADRATIC) {
+            r = r.create_button(|b| {
+                b
+                    .custom_id(ID_BUILD_SCORE_BTN)
+                    .style(ButtonStyle::Secondary)
+                    .label(
+                        if vi.kind == VOTE_LSCORE {
+                            format!("Scores = {}, Budget = {}", vi.score_settings.range_str(), vi.score_settings.budget)
+                        } else if vi.kind == VOTE_CUMULATIVE {
+                            format!("Points per Voter = {}", vi.score_settings.budget)
+                        } else if vi.kind == VOTE_QUADRATIC {
+                            format!("Votes = {}, Credits = {}", vi.score_settings.range_str(), vi.score_settings.budget)
+                        } else {
+                            format!("Scores = {}", vi.score_settings.range_str())
+                        }
+                    )
+            });
+        }
+
+        r
+    });
+    // add/edit choices
+    c = c.create_action_row(|mut r| {
+        r = r.create_button(|b|{
+            b
+                .custom_id(ID_BUILD_CHOICE_BTN)
+                .style(ButtonStyle::Primary)
+                .label(
+                    if vi.vals.len() == 0 {
+                        String::from("Add Vote Choices")
+                    } else {
+                        format!("Edit Choices ({} choices)", vi.vals.len())
+                    }
+                )
+        });
+
+        // quorum and threshold
+        r = r.create_button(|b| {
+            b
+                .custom_id(ID_BUILD_DECIDE_BTN)
+                .style(ButtonStyle::Secondary)
+                .label(vi.get_decide_str())
+        });
+
+        // write-ins
+        r = r.create_button(|b| {
+            b
+                .custom_id(ID_BUILD_WRITEIN_BTN)
+                .style(ButtonStyle::Secondary)
+                .label(format!("Allow Write-ins = {}",
+                    if vi.write_ins {
+                        "Yes"
+                    } else {
+                        "No"
+                    }
+                ))
+        });
+
+        // veto round
+        r = r.create_button(|b| {
+            b
+                .custom_id(ID_BUILD_VETO_BTN)
+                .style(ButtonStyle::Secondary)
+                .label(format!("Veto Round = {}",
+                    if vi.veto_phase {
+                        "Yes"
+                    } else {
+                        "No"
+                    }
+                ))
+        });
+
+        // none of the above
         r = r.create_button(|b| {
             b
-                .custom_id(ID_BUILD_DUR_BTN)
+                .custom_id(ID_BUILD_NONE_BTN)
                 .style(ButtonStyle::Secondary)
-                .label(format!("Vote Timeout = {}",
-                    vi.get_timeout_str(" hr")
+                .label(format!("Add \"{}\" = {}",
+                    NONE_CHOICE,
+                    if vi.none_option {
+                        "Yes"
+                    } else {
+                        "No"
+                    }
                 ))
         });
 
         r
     });
-    // add/edit choices
-    c = c.create_action_row(|r| {
-        r.create_button(|b|{
+    // submit, cancel
+    c = c.create_action_row(|mut r| {
+        // role weights, a setting but there is no room left with the others
+        r = r.create_button(|b| {
             b
-                .custom_id(ID_BUILD_CHOICE_BTN)
-                .style(ButtonStyle::Primary)
+                .custom_id(ID_BUILD_ROLES_BTN)
+                .style(ButtonStyle::Secondary)
                 .label(
-                    if vi.vals.len() == 0 {
-                        String::from("Add Vote Choices")
+                    if vi.role_weights.is_empty() {
+                        String::from("Role Weights = None")
                     } else {
-                        format!("Edit Choices ({} choices)", vi.vals.len())
+                        format!("Role Weights = {} Roles", vi.role_weights.len())
                     }
                 )
-        })
-    });
-    // submit, cancel
-    c = c.create_action_row(|mut r| {
+        });
+        if vi.kind.is_time_budget() {
+            r = r.create_button(|b| {
+                b
+                    .custom_id(ID_BUILD_BUDGET_BTN)
+                    .style(if vi.time_budget == 0 { ButtonStyle::Primary } else { ButtonStyle::Secondary })
+                    .label(
+                        if vi.time_budget == 0 {
+                            String::from("Set Time Budget")
+                        } else {
+                            format!("Time Budget = {}", minutes_str(vi.time_budget))
+                        }
+                    )
+            });
+        }
         r = r.create_button(|b| {
             b
                 .custom_id(ID_BUILD_SUBMIT)
@@ -1279,7 +3802,7 @@ fn create_dm_vote_comp<'a, 'b>(mut c: &'a mut CreateComponents, vi: &'b VoteInfo
 }
 
 async fn handle_dm_vote(ctx: Context, msg: Message) {
-    let mut vi = VoteInfo::new();
+    let mut vi = VoteInfo::new(msg.author.id);
 
     // create initial dm to the person creating the vote
     // this will get edited as options are changed
@@ -1312,6 +3835,9 @@ async fn handle_dm_vote(ctx: Context, msg: Message) {
                     ID_BUILD_TYPE => {
                         // collect the chosen type
                         vi.kind = VoteType::from_string(&interaction.data.values[0]);
+                        if vi.tie_break == TieBreak::Runoff && !vi.kind.has_runoff() {
+                            vi.tie_break = TieBreak::ShowAll;
+                        }
                     },
                     ID_BUILD_SUG_BTN => {
                         vi.take_sugs = !vi.take_sugs;
@@ -1322,6 +3848,24 @@ async fn handle_dm_vote(ctx: Context, msg: Message) {
                     ID_BUILD_VOTEONE_BTN => {
                         vi.vote_once = !vi.vote_once;
                     },
+                    ID_BUILD_NONE_BTN => {
+                        vi.none_option = !vi.none_option;
+                    },
+                    ID_BUILD_WRITEIN_BTN => {
+                        vi.write_ins = !vi.write_ins;
+                    },
+                    ID_BUILD_VETO_BTN => {
+                        vi.veto_phase = !vi.veto_phase;
+                    },
+                    ID_BUILD_BORDA_BTN => {
+                        vi.borda_variant = vi.borda_variant.next();
+                    },
+                    ID_BUILD_TIE_BTN => {
+                        vi.tie_break = vi.tie_break.next();
+                        if vi.tie_break == TieBreak::Runoff && !vi.kind.has_runoff() {
+                            vi.tie_break = vi.tie_break.next();
+                        }
+                    },
                     ID_BUILD_PING_BTN => {
                         vi.ping_chan += 1;
                         if vi.ping_chan >= 3 {
@@ -1381,6 +3925,222 @@ async fn handle_dm_vote(ctx: Context, msg: Message) {
 
                         update_dm = false;
                     },
+                    ID_BUILD_WINNERS_BTN => {
+                        // send modal to get a different number of winners
+                        interaction.create_interaction_response(&ctx, |resp| {
+                            resp.kind(InteractionResponseType::Modal).interaction_response_data(|d| {
+                                d
+                                    .custom_id(ID_BUILD_WINNERS_INPUT)
+                                    .title("Number of Winners")
+                                    .components(|c| {
+                                        c.create_action_row(|r| {
+                                            r.create_input_text(|t| {
+                                                t
+                                                    .custom_id(ID_BUILD_WINNERS_INPUT_TXT)
+                                                    .style(InputTextStyle::Short)
+                                                    .label(format!("# Winners (1 to {})", MAX_WINNERS))
+                                                    .min_length(1)
+                                                    .max_length(2)
+                                                    .required(true)
+                                                    .value(vi.num_winners.to_string())
+                                            })
+                                        })
+                                    })
+                            })
+                        }).await.unwrap();
+
+                        update_dm = false;
+                    },
+                    ID_BUILD_SCORE_BTN => {
+                        // send modal to get the score settings
+                        let ss = vi.score_settings;
+                        // cumulative only has the points to spread
+                        let inputs = if vi.kind == VOTE_CUMULATIVE {
+                            vec![(ID_BUILD_SCORE_INPUT_BUDGET, "Points per Voter", ss.budget)]
+                        } else if vi.kind == VOTE_QUADRATIC {
+                            vec![
+                                (ID_BUILD_SCORE_INPUT_MIN, "Fewest Votes on a Choice", ss.min),
+                                (ID_BUILD_SCORE_INPUT_MAX, "Most Votes on a Choice", ss.max),
+                                (ID_BUILD_SCORE_INPUT_BUDGET, "Credits per Voter", ss.budget),
+                            ]
+                        } else {
+                            let mut inputs = vec![
+                                (ID_BUILD_SCORE_INPUT_MIN, "Lowest Score", ss.min),
+                                (ID_BUILD_SCORE_INPUT_MAX, "Highest Score", ss.max),
+                                (ID_BUILD_SCORE_INPUT_STEP, "Score Step (0 for any)", ss.step),
+                            ];
+                            // only limited score has a budget
+                            if vi.kind == VOTE_LSCORE {
+                                inputs.push((ID_BUILD_SCORE_INPUT_BUDGET, "Limited Score Budget", ss.budget));
+                            }
+                            inputs
+                        };
+                        interaction.create_interaction_response(&ctx, |resp| {
+                            resp.kind(InteractionResponseType::Modal).interaction_response_data(|d| {
+                                d
+                                    .custom_id(ID_BUILD_SCORE_INPUT)
+                                    .title("Score Settings")
+                                    .components(|c| {
+                                        for (id, label, value) in &inputs {
+                                            c.create_action_row(|r| {
+                                                r.create_input_text(|t| {
+                                                    t
+                                                        .custom_id(*id)
+                                                        .style(InputTextStyle::Short)
+                                                        .label(*label)
+                                                        .min_length(1)
+                                                        .max_length(8)
+                                                        .required(true)
+                                                        .value(value.to_string())
+                                                })
+                                            });
+                                        }
+                                        c
+                                    })
+                            })
+                        }).await.unwrap();
+
+                        update_dm = false;
+                    },
+                    ID_BUILD_SELECT_BTN => {
+                        // send modal to get the selection limits
+                        let sl = vi.select_limits;
+                        interaction.create_interaction_response(&ctx, |resp| {
+                            resp.kind(InteractionResponseType::Modal).interaction_response_data(|d| {
+                                d
+                                    .custom_id(ID_BUILD_SELECT_INPUT)
+                                    .title("Approval Selections")
+                                    .components(|c| {
+                                        for (id, label, value) in [
+                                            (ID_BUILD_SELECT_INPUT_MIN, "Minimum Selections", sl.min),
+                                            (ID_BUILD_SELECT_INPUT_MAX, "Maximum Selections (0 for no limit)", sl.max),
+                                        ] {
+                                            c.create_action_row(|r| {
+                                                r.create_input_text(|t| {
+                                                    t
+                                                        .custom_id(id)
+                                                        .style(InputTextStyle::Short)
+                                                        .label(label)
+                                                        .min_length(1)
+                                                        .max_length(3)
+                                                        .required(true)
+                                                        .value(value.to_string())
+                                                })
+                                            });
+                                        }
+                                        c
+                                    })
+                            })
+                        }).await.unwrap();
+
+                        update_dm = false;
+                    },
+                    ID_BUILD_ROLES_BTN => {
+                        // send modal to get the role weights, by name
+                        let current = if let Some(gid) = msg.guild_id {
+                            let roles = gid.roles(&ctx).await.unwrap();
+                            vi.role_weights.iter().filter_map(|(rid, w)| {
+                                roles.get(rid).map(|role| format!("{} = {}", role.name, w))
+                            }).collect::<Vec<String>>().join("\n")
+                        } else {
+                            String::new()
+                        };
+
+                        interaction.create_interaction_response(&ctx, |resp| {
+                            resp.kind(InteractionResponseType::Modal).interaction_response_data(|d| {
+                                d
+                                    .custom_id(ID_BUILD_ROLES_INPUT)
+                                    .title("Role Weights")
+                                    .components(|c| {
+                                        c.create_action_row(|r| {
+                                            r.create_input_text(|mut t| {
+                                                t = t
+                                                    .custom_id(ID_BUILD_ROLES_INPUT_TXT)
+                                                    .style(InputTextStyle::Paragraph)
+                                                    .label(format!("Role Name = Weight (1-{}), one per line", MAX_ROLE_WEIGHT))
+                                                    .max_length(1200)
+                                                    .required(false);
+
+                                                if !current.is_empty() {
+                                                    t = t.value(&current);
+                                                }
+
+                                                t
+                                            })
+                                        })
+                                    })
+                            })
+                        }).await.unwrap();
+
+                        update_dm = false;
+                    },
+                    ID_BUILD_BUDGET_BTN => {
+                        // send modal to get the time to fill
+                        let hrs = (vi.time_budget as f64) / 60.0;
+                        interaction.create_interaction_response(&ctx, |resp| {
+                            resp.kind(InteractionResponseType::Modal).interaction_response_data(|d| {
+                                d
+                                    .custom_id(ID_BUILD_BUDGET_INPUT)
+                                    .title("Time Budget")
+                                    .components(|c| {
+                                        c.create_action_row(|r| {
+                                            r.create_input_text(|t| {
+                                                t
+                                                    .custom_id(ID_BUILD_BUDGET_INPUT_TXT)
+                                                    .style(InputTextStyle::Short)
+                                                    .label("Hours to fill, choices end like \"(90m)\"")
+                                                    .min_length(1)
+                                                    .max_length(5)
+                                                    .required(true)
+                                                    .value(format!("{:.2}", hrs))
+                                            })
+                                        })
+                                    })
+                            })
+                        }).await.unwrap();
+
+                        update_dm = false;
+                    },
+                    ID_BUILD_DECIDE_BTN => {
+                        // send modal to get the quorum and threshold
+                        let quorum = vi.quorum;
+                        let threshold = vi.threshold;
+                        interaction.create_interaction_response(&ctx, |resp| {
+                            resp.kind(InteractionResponseType::Modal).interaction_response_data(|d| {
+                                d
+                                    .custom_id(ID_BUILD_DECIDE_INPUT)
+                                    .title("Decision Rules")
+                                    .components(|c| {
+                                        c.create_action_row(|r| {
+                                            r.create_input_text(|t| {
+                                                t
+                                                    .custom_id(ID_BUILD_DECIDE_INPUT_QUORUM)
+                                                    .style(InputTextStyle::Short)
+                                                    .label("Minimum # Voters (0 for none)")
+                                                    .min_length(1)
+                                                    .max_length(4)
+                                                    .required(true)
+                                                    .value(quorum.to_string())
+                                            })
+                                        })
+                                        .create_action_row(|r| {
+                                            r.create_input_text(|t| {
+                                                t
+                                                    .custom_id(ID_BUILD_DECIDE_INPUT_THRESHOLD)
+                                                    .style(InputTextStyle::Short)
+                                                    .label("% Voters Winner Is Top Choice For (0-100)")
+                                                    .min_length(1)
+                                                    .max_length(5)
+                                                    .required(true)
+                                                    .value(threshold.to_string())
+                                            })
+                                        })
+                                    })
+                            })
+                        }).await.unwrap();
+
+                        update_dm = false;
+                    },
                     ID_BUILD_CHOICE_BTN => {
                         // send modal to edit choices
                         interaction.create_interaction_response(&ctx, |resp| {
@@ -1489,6 +4249,144 @@ async fn handle_dm_vote(ctx: Context, msg: Message) {
                             panic!("No input found on timeout option dm modal");
                         }
                     },
+                    ID_BUILD_WINNERS_INPUT => {
+                        if let ActionRowComponent::InputText(it) = &interaction.data.components[0].components[0] {
+                            if let Ok(n) = it.value.parse::<usize>() {
+                                if (1..=MAX_WINNERS).contains(&n) {
+                                    vi.num_winners = n;
+                                } else {
+                                    println!("Not accepting bad number of winners");
+                                }
+                            } else {
+                                println!("Not accepting non-number number of winners");
+                            }
+                        } else {
+                            panic!("No input found on winners option dm modal");
+                        }
+                    },
+                    ID_BUILD_SCORE_INPUT => {
+                        let mut ss = vi.score_settings;
+                        let mut parsed = true;
+                        for row in &interaction.data.components {
+                            if let ActionRowComponent::InputText(it) = &row.components[0] {
+                                if let Ok(v) = it.value.parse::<f32>() {
+                                    match &it.custom_id[..] {
+                                        ID_BUILD_SCORE_INPUT_MIN => ss.min = v,
+                                        ID_BUILD_SCORE_INPUT_MAX => ss.max = v,
+                                        ID_BUILD_SCORE_INPUT_STEP => ss.step = v,
+                                        ID_BUILD_SCORE_INPUT_BUDGET => ss.budget = v,
+                                        _ => panic!("Unknown input on score option dm modal"),
+                                    }
+                                } else {
+                                    parsed = false;
+                                }
+                            } else {
+                                panic!("No input found on score option dm modal");
+                            }
+                        }
+
+                        // "NaN" and "inf" parse fine, but would get past every check below
+                        let finite = [ss.min, ss.max, ss.step, ss.budget].iter().all(|v| v.is_finite());
+                        if !parsed || !finite {
+                            println!("Not accepting non-number score settings");
+                        } else if ss.min >= ss.max || ss.step < 0.0 || ss.step > (ss.max - ss.min) || ss.budget <= 0.0
+                            || (vi.kind == VOTE_CUMULATIVE && ss.budget.fract() != 0.0) {
+                            println!("Not accepting bad score settings");
+                        } else {
+                            vi.score_settings = ss;
+                        }
+                    },
+                    ID_BUILD_SELECT_INPUT => {
+                        let mut sl = vi.select_limits;
+                        let mut parsed = true;
+                        for row in &interaction.data.components {
+                            if let ActionRowComponent::InputText(it) = &row.components[0] {
+                                if let Ok(v) = it.value.parse::<usize>() {
+                                    match &it.custom_id[..] {
+                                        ID_BUILD_SELECT_INPUT_MIN => sl.min = v,
+                                        ID_BUILD_SELECT_INPUT_MAX => sl.max = v,
+                                        _ => panic!("Unknown input on selection option dm modal"),
+                                    }
+                                } else {
+                                    parsed = false;
+                                }
+                            } else {
+                                panic!("No input found on selection option dm modal");
+                            }
+                        }
+
+                        if !parsed {
+                            println!("Not accepting non-number selection limits");
+                        } else if sl.max > 0 && sl.min > sl.max {
+                            println!("Not accepting bad selection limits");
+                        } else {
+                            vi.select_limits = sl;
+                        }
+                    },
+                    ID_BUILD_ROLES_INPUT => {
+                        if let ActionRowComponent::InputText(it) = &interaction.data.components[0].components[0] {
+                            if let Some(gid) = msg.guild_id {
+                                let roles = gid.roles(&ctx).await.unwrap();
+                                let mut role_weights: HashMap<RoleId, u64> = HashMap::new();
+
+                                for line in it.value.lines().filter(|l| !l.trim().is_empty()) {
+                                    let parsed = line.rsplit_once('=').and_then(|(name, w)| {
+                                        let name = name.trim();
+                                        let rid = roles.values().find(|role| role.name.eq_ignore_ascii_case(name))?.id;
+                                        let w = w.trim().parse::<u64>().ok()?;
+                                        (1..=MAX_ROLE_WEIGHT).contains(&w).then_some((rid, w))
+                                    });
+
+                                    match parsed {
+                                        Some((rid, w)) => {
+                                            role_weights.insert(rid, w);
+                                        },
+                                        None => println!("Not accepting bad role weight {:?}", line),
+                                    }
+                                }
+
+                                vi.role_weights = role_weights;
+                            } else {
+                                println!("Not accepting role weights outside of a server");
+                            }
+                        } else {
+                            panic!("No input found on role weights dm modal");
+                        }
+                    },
+                    ID_BUILD_BUDGET_INPUT => {
+                        if let ActionRowComponent::InputText(it) = &interaction.data.components[0].components[0] {
+                            match it.value.parse::<f64>() {
+                                Ok(hrs) if hrs > 0.0 && hrs <= MAX_BUDGET_HR => vi.time_budget = (hrs * 60.0).round().max(1.0) as u32,
+                                _ => println!("Not accepting bad time budget"),
+                            }
+                        } else {
+                            panic!("No input found on time budget dm modal");
+                        }
+                    },
+                    ID_BUILD_DECIDE_INPUT => {
+                        for row in &interaction.data.components {
+                            if let ActionRowComponent::InputText(it) = &row.components[0] {
+                                match &it.custom_id[..] {
+                                    ID_BUILD_DECIDE_INPUT_QUORUM => {
+                                        if let Ok(n) = it.value.parse::<usize>() {
+                                            vi.quorum = n;
+                                        } else {
+                                            println!("Not accepting non-number quorum");
+                                        }
+                                    },
+                                    ID_BUILD_DECIDE_INPUT_THRESHOLD => {
+                                        match it.value.parse::<f32>() {
+                                            Ok(p) if (0.0..=100.0).contains(&p) => vi.threshold = p,
+                                            _ => println!("Not accepting bad threshold"),
+                                        }
+                                    },
+                                    _ => panic!("Unknown input on decision option dm modal"),
+                                }
+                            } else {
+                                panic!("No input found on decision option dm modal");
+                            }
+                        }
+                    },
                     ID_BUILD_VAL_INPUT => {
                         if let ActionRowComponent::InputText(it) = &interaction.data.components[0].components[0] {
                             let v = content_safe(&ctx, &it.value, &ContentSafeOptions::default(), &[]);
@@ -1530,6 +4428,8 @@ async fn handle_dm_vote(ctx: Context, msg: Message) {
         if vi.take_sugs {
             // start suggestion path
             handle_suggestion_phase(&ctx, &msg.author, msg.channel_id, vi).await;
+        } else if vi.veto_phase {
+            handle_veto_phase(&ctx, &msg.author, msg.channel_id, vi).await;
         } else {
             // just start the vote
             start_vote(&ctx, msg.channel_id, vi).await
@@ -1565,3 +4465,190 @@ async fn main() {
         println!("Client error: {:?}", why);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a strict ranking, one choice per rank
+    fn ranked(order: &[usize]) -> Vec<Vec<usize>> {
+        order.iter().map(|c| vec![*c]).collect()
+    }
+
+    fn repeat<T: Clone>(groups: &[(usize, T)]) -> Vec<T> {
+        groups.iter().flat_map(|(n, b)| std::iter::repeat_n(b.clone(), *n)).collect()
+    }
+
+    // how many ballots rank a over b, with unranked choices last
+    fn pairwise(ballots: &[Vec<Vec<usize>>], num_choices: usize) -> HashMap<(usize, usize), u64> {
+        let mut pairs: HashMap<(usize, usize), u64> = HashMap::new();
+        for b in ballots {
+            let rank = |c: usize| b.iter().position(|g| g.contains(&c)).unwrap_or(usize::MAX);
+            for x in 0..num_choices {
+                for y in 0..num_choices {
+                    if rank(x) < rank(y) {
+                        *pairs.entry((x, y)).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        pairs
+    }
+
+    // the Tennessee capital example: Memphis, Nashville, Chattanooga, Knoxville
+    fn tennessee() -> Vec<Vec<Vec<usize>>> {
+        repeat(&[
+            (42, ranked(&[0, 1, 2, 3])),
+            (26, ranked(&[1, 2, 3, 0])),
+            (15, ranked(&[2, 3, 1, 0])),
+            (17, ranked(&[3, 2, 1, 0])),
+        ])
+    }
+
+    #[test]
+    fn irv_tennessee() {
+        let (winners, rounds) = instant_runoff(&tennessee(), 4, 1);
+        assert_eq!(winners[0], (3, 0));
+        assert_eq!(rounds.len(), 3);
+        assert_eq!(rounds[0].eliminated, vec![2]);
+        assert_eq!(rounds[1].eliminated, vec![1]);
+        assert_eq!(rounds[2].counts, vec![(3, 58.0), (0, 42.0)]);
+    }
+
+    #[test]
+    fn stv_surplus_transfer() {
+        // quota is 9/3 + 1 = 4, so A passes on 2 of its 6 votes to B
+        let ballots = repeat(&[
+            (6, ranked(&[0, 1])),
+            (2, ranked(&[2])),
+            (1, ranked(&[1])),
+        ]);
+        let (quota, winners, rounds) = single_transferable_vote(&ballots, 3, 2);
+        assert_eq!(quota, 4.0);
+        assert_eq!(winners, vec![(0, 0), (1, 1)]);
+        assert_eq!(rounds[0].elected, Some((0, 2.0)));
+        assert!((rounds[1].counts[0].1 - 3.0).abs() < 1e-9);
+        assert_eq!(rounds[1].eliminated, Some(2));
+    }
+
+    #[test]
+    fn stv_tied_ballots_split() {
+        // a ballot tying A and B gives each half a vote
+        let ballots = repeat(&[
+            (2, vec![vec![0, 1]]),
+            (1, ranked(&[0])),
+            (1, ranked(&[2])),
+        ]);
+        let (quota, winners, rounds) = single_transferable_vote(&ballots, 3, 1);
+        assert_eq!(quota, 3.0);
+        assert_eq!(rounds[0].counts, vec![(0, 2.0), (1, 1.0), (2, 1.0)]);
+        assert_eq!(rounds[0].eliminated, Some(2));
+        assert_eq!(winners, vec![(0, 0)]);
+    }
+
+    #[test]
+    fn stv_tied_surplus_only_scales_elected_share() {
+        // quota is 9/3 + 1 = 4, A gets 3 + 4/2 = 5 and passes on 1/5 of it
+        let ballots = repeat(&[
+            (4, vec![vec![0, 1]]),
+            (3, ranked(&[0])),
+            (2, ranked(&[2])),
+        ]);
+        let (quota, winners, rounds) = single_transferable_vote(&ballots, 3, 2);
+        assert_eq!(quota, 4.0);
+        assert_eq!(rounds[0].counts, vec![(0, 5.0), (1, 2.0), (2, 2.0)]);
+        assert_eq!(rounds[0].elected, Some((0, 1.0)));
+        // the tied ballots keep their half for B and a fifth of the half that went to A
+        assert_eq!(rounds[1].counts[0].0, 1);
+        assert!((rounds[1].counts[0].1 - 4.0 * 0.6).abs() < 1e-9);
+        assert_eq!(winners, vec![(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn ranked_pairs_tennessee() {
+        let (winners, locked, skipped) = ranked_pairs(&pairwise(&tennessee(), 4), 4, 4);
+        assert_eq!(winners, vec![(1, 0), (2, 1), (3, 2), (0, 3)]);
+        assert_eq!((locked[0].winner, locked[0].loser, locked[0].support), (2, 3, 83));
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn ranked_pairs_skips_cycle() {
+        // A beats B 8-4, B beats C 9-3, C beats A 7-5, the weakest link is dropped
+        let ballots = repeat(&[
+            (5, ranked(&[0, 1, 2])),
+            (4, ranked(&[1, 2, 0])),
+            (3, ranked(&[2, 0, 1])),
+        ]);
+        let (winners, locked, skipped) = ranked_pairs(&pairwise(&ballots, 3), 3, 1);
+        assert_eq!(winners, vec![(0, 0)]);
+        assert_eq!(locked.iter().map(|l| (l.winner, l.loser)).collect::<Vec<_>>(), vec![(1, 2), (0, 1)]);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!((skipped[0].winner, skipped[0].loser, skipped[0].support, skipped[0].opposition), (2, 0, 7, 5));
+    }
+
+    #[test]
+    fn mj_cmp_medians() {
+        assert_eq!(mj_cmp(&[2, 3, 4], &[0, 1, 2]), Ordering::Greater);
+        // same median, so drop it and compare the next one
+        assert_eq!(mj_cmp(&[0, 1, 2], &[1, 1, 1]), Ordering::Less);
+        // the lower median is used for an even number of grades
+        assert_eq!(mj_cmp(&[1, 4], &[2, 2]), Ordering::Less);
+        assert_eq!(mj_cmp(&[1, 2, 3], &[1, 2, 3]), Ordering::Equal);
+    }
+
+    #[test]
+    fn pav_is_proportional() {
+        // 3 voters want A and B, 2 want C: approval would seat A and B, PAV seats A and C
+        let ballots = repeat(&[(3, vec![0, 1]), (2, vec![2])]);
+        let (set, score) = proportional_approval(&ballots, 3, 2).unwrap();
+        assert_eq!(set, vec![0, 2]);
+        assert!((score - 5.0).abs() < 1e-9);
+
+        let picks = sequential_proportional_approval(&ballots, 3, 2);
+        assert_eq!(picks.iter().map(|(c, _)| *c).collect::<Vec<_>>(), vec![0, 2]);
+    }
+
+    #[test]
+    fn pav_gives_up_on_huge_sets() {
+        assert!(proportional_approval(&[vec![0]], 40, 10).is_none());
+    }
+
+    #[test]
+    fn time_budget_knapsack() {
+        let utility = [10.0, 6.0, 6.0, 1.0];
+        let durations = [Some(120), Some(60), Some(60), None];
+        // two short ones beat the long one
+        assert_eq!(fill_time_budget(&utility, &durations, 120), vec![1, 2]);
+        assert_eq!(fill_time_budget(&utility, &durations, 180), vec![0, 1]);
+        assert!(fill_time_budget(&utility, &durations, 30).is_empty());
+        assert!(fill_time_budget(&[0.0], &[Some(10)], 60).is_empty());
+    }
+
+    #[test]
+    fn shorten_choice_multibyte() {
+        let long = "é".repeat(MAX_CHOICE_LEN * 2);
+        assert!(shorten_choice(&long).chars().count() <= MAX_CHOICE_LEN + 3);
+    }
+
+    #[test]
+    fn results_fit_in_a_message() {
+        let vals: Vec<String> = (0..20).map(|i| format!("{} {}", i, "x".repeat(MAX_CHOICE_LEN - 3))).collect();
+        for kind in [VOTE_IRV, VOTE_STV, VOTE_SCHULZE, VOTE_RPAIRS, VOTE_COPELAND, VOTE_MINIMAX, VOTE_BORDA] {
+            let mut vi = VoteInfo::new(UserId(0));
+            vi.kind = kind;
+            vi.num_winners = if kind == VOTE_STV { 3 } else { 1 };
+            vi.vals = vals.clone();
+            let mut vote = Vote::new(&vi);
+            // every voter rotates the order, so there's a long cycle and every round is close
+            for u in 0..40u64 {
+                let ranks = (0..vals.len()).map(|c| (c, (c + u as usize) % vals.len())).collect();
+                vote.submittedvotes.insert(UserId(u + 1), CastVotes::Rank(ranks));
+            }
+            vote.close();
+            let results = clip_lines(&vote.get_results(&vals, ""), MAX_MSG_LEN);
+            assert!(results.chars().count() <= MAX_MSG_LEN, "{:?}", kind);
+            assert!(vote.get_results(&vals, "").chars().count() <= MAX_MSG_LEN, "{:?} needed clipping", kind);
+        }
+    }
+}